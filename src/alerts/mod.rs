@@ -0,0 +1,247 @@
+use bevy::prelude::*;
+use std::fmt;
+
+use crate::buildings::helpers::{Building, BuildingRotation, BuildingStatus, Footprint, TILE_SIZE};
+use crate::fluids::FluidPorts;
+use crate::fluids::network::{FlowDevice, PipeNetworks, port_tile};
+use crate::power::PowerConsumer;
+
+// Problems have to last this long before they are reported, so buildings
+// that stall for a frame or two between batches don't flicker
+const ALERT_DELAY: f32 = 2.0;
+
+const ICON_SIZE: f32 = 16.0;
+
+pub struct AlertsPlugin;
+
+impl Plugin for AlertsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_status_icons).add_systems(
+            Update,
+            (
+                attach_status_icons,
+                update_building_alerts,
+                update_status_icons,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertKind {
+    NoInput,
+    OutputBlocked,
+    NoPower,
+    Misconfigured,
+    Depleted,
+}
+
+impl AlertKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertKind::NoInput => "No input",
+            AlertKind::OutputBlocked => "Output blocked",
+            AlertKind::NoPower => "No power",
+            AlertKind::Misconfigured => "Misconfigured",
+            AlertKind::Depleted => "Deposit depleted",
+        }
+    }
+
+    pub fn atlas_index(&self) -> usize {
+        match self {
+            AlertKind::NoInput => 0,
+            AlertKind::OutputBlocked => 1,
+            AlertKind::NoPower => 2,
+            AlertKind::Misconfigured => 3,
+            AlertKind::Depleted => 4,
+        }
+    }
+
+    // Plain color for egui labels, matches the icon
+    pub fn ui_color(&self) -> [u8; 3] {
+        match self {
+            AlertKind::NoInput => [230, 70, 60],
+            AlertKind::OutputBlocked => [251, 185, 84],
+            AlertKind::NoPower => [255, 230, 80],
+            AlertKind::Misconfigured => [200, 120, 220],
+            AlertKind::Depleted => [180, 140, 100],
+        }
+    }
+}
+
+impl fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The worst problem a building currently has, once it has lasted `ALERT_DELAY`.
+#[derive(Component, Default)]
+pub struct BuildingAlert {
+    candidate: Option<AlertKind>,
+    held_for: f32,
+}
+
+impl BuildingAlert {
+    pub fn active(&self) -> Option<AlertKind> {
+        if self.held_for >= ALERT_DELAY {
+            self.candidate
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Resource)]
+struct StatusIconAssets {
+    texture: Handle<Image>,
+    atlas_layout: Handle<TextureAtlasLayout>,
+}
+
+#[derive(Component)]
+struct StatusIcon;
+
+fn setup_status_icons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let texture = asset_server.load("textures/status_icons.png");
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 5, 1, None, None);
+
+    commands.insert_resource(StatusIconAssets {
+        texture,
+        atlas_layout: texture_atlas_layouts.add(layout),
+    });
+}
+
+fn attach_status_icons(
+    mut commands: Commands,
+    assets: Res<StatusIconAssets>,
+    q_buildings: Query<
+        Entity,
+        (
+            With<Building>,
+            Or<(
+                With<BuildingStatus>,
+                With<PowerConsumer>,
+                With<FluidPorts>,
+                With<FlowDevice>,
+            )>,
+            Without<BuildingAlert>,
+        ),
+    >,
+) {
+    for building in q_buildings.iter() {
+        commands
+            .entity(building)
+            .insert(BuildingAlert::default())
+            .with_children(|parent| {
+                parent.spawn((
+                    StatusIcon,
+                    Sprite {
+                        image: assets.texture.clone(),
+                        texture_atlas: Some(TextureAtlas {
+                            layout: assets.atlas_layout.clone(),
+                            index: 0,
+                        }),
+                        ..default()
+                    },
+                    Transform::default(),
+                    Visibility::Hidden,
+                    Pickable::IGNORE,
+                ));
+            });
+    }
+}
+
+fn update_building_alerts(
+    time: Res<Time>,
+    networks: Res<PipeNetworks>,
+    mut q_buildings: Query<(
+        &Transform,
+        &BuildingRotation,
+        &mut BuildingAlert,
+        Option<&BuildingStatus>,
+        Option<&PowerConsumer>,
+        Option<&FluidPorts>,
+        Option<&FlowDevice>,
+    )>,
+) {
+    for (transform, rotation, mut alert, status, power, ports, device) in q_buildings.iter_mut() {
+        let has_error = |offset: Vec2| {
+            networks
+                .at(port_tile(transform.translation, *rotation, offset))
+                .is_some_and(|network| network.error.is_some())
+        };
+
+        let mut misconfigured =
+            ports.is_some_and(|ports| ports.0.iter().any(|port| has_error(port.offset)));
+
+        // Devices with both ends on one network never move anything
+        if device.is_some() {
+            let back = port_tile(transform.translation, *rotation, Vec2::NEG_X);
+            let front = port_tile(transform.translation, *rotation, Vec2::X);
+
+            misconfigured |= has_error(Vec2::NEG_X) || has_error(Vec2::X);
+            misconfigured |= networks.index_at(back).is_some()
+                && networks.index_at(back) == networks.index_at(front);
+        }
+
+        // Worst problem first
+        let candidate = if misconfigured {
+            Some(AlertKind::Misconfigured)
+        } else if power.is_some_and(|power| power.satisfaction <= 0.0) {
+            Some(AlertKind::NoPower)
+        } else {
+            match status {
+                Some(BuildingStatus::InputStarved) => Some(AlertKind::NoInput),
+                Some(BuildingStatus::OutputBlocked) => Some(AlertKind::OutputBlocked),
+                Some(BuildingStatus::Depleted) => Some(AlertKind::Depleted),
+                _ => None,
+            }
+        };
+
+        if candidate == alert.candidate {
+            alert.held_for += time.delta_secs();
+        } else {
+            alert.candidate = candidate;
+            alert.held_for = 0.0;
+        }
+    }
+}
+
+fn update_status_icons(
+    q_buildings: Query<(&Transform, &BuildingAlert, Option<&Footprint>), Without<StatusIcon>>,
+    mut q_icons: Query<(&ChildOf, &mut Sprite, &mut Visibility, &mut Transform), With<StatusIcon>>,
+) {
+    for (child_of, mut sprite, mut visibility, mut transform) in q_icons.iter_mut() {
+        let Ok((parent_transform, alert, footprint)) = q_buildings.get(child_of.parent()) else {
+            continue;
+        };
+
+        let Some(kind) = alert.active() else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        *visibility = Visibility::Inherited;
+        if let Some(ref mut atlas) = sprite.texture_atlas {
+            atlas.index = kind.atlas_index();
+        }
+
+        // Top right corner of the building, undoing the parent's rotation so
+        // the icon stays upright on pumps and valves
+        let size = footprint.map(|f| f.0).unwrap_or(1) as f32 * TILE_SIZE;
+        let corner = Vec3::new(
+            size / 2.0 - ICON_SIZE / 2.0,
+            size / 2.0 - ICON_SIZE / 2.0,
+            3.0,
+        );
+        let undo = parent_transform.rotation.inverse();
+
+        transform.translation = undo * corner;
+        transform.rotation = undo;
+    }
+}
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+
+use crate::buildings::helpers::BuildingStatus;
+
+// Buildings have to stop working for this long before they wind down, so ones
+// that stall for a frame between batches keep running instead of restarting
+const STOP_DELAY: f32 = 0.5;
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, animate_buildings);
+    }
+}
+
+/// A run of atlas frames played at a fixed rate.
+#[derive(Clone, Copy, Debug)]
+pub struct AnimationClip {
+    pub first: usize,
+    pub last: usize,
+    pub fps: f32,
+    pub looping: bool,
+}
+
+impl AnimationClip {
+    pub fn looping(first: usize, last: usize, fps: f32) -> Self {
+        Self {
+            first,
+            last,
+            fps,
+            looping: true,
+        }
+    }
+
+    pub fn once(first: usize, last: usize, fps: f32) -> Self {
+        Self {
+            first,
+            last,
+            fps,
+            looping: false,
+        }
+    }
+
+    pub fn still(frame: usize) -> Self {
+        Self::looping(frame, frame, 1.0)
+    }
+
+    fn len(&self) -> usize {
+        self.last - self.first + 1
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Starting,
+    Working,
+}
+
+/// Per building animation that follows its `BuildingStatus`.
+#[derive(Component, Debug)]
+pub struct BuildingAnimation {
+    pub idle: AnimationClip,
+    pub startup: Option<AnimationClip>,
+    pub working: AnimationClip,
+    pub state: AnimationState,
+    frame: usize,
+    elapsed: f32,
+    // Frame the working loop starts from
+    phase: usize,
+    // How long the building has not been working
+    stopped_for: f32,
+}
+
+impl BuildingAnimation {
+    pub fn new(idle: AnimationClip, working: AnimationClip) -> Self {
+        Self {
+            idle,
+            startup: None,
+            working,
+            state: AnimationState::Idle,
+            frame: 0,
+            elapsed: 0.0,
+            phase: 0,
+            stopped_for: 0.0,
+        }
+    }
+
+    // Machines spin up slowly through their frames before settling into the full speed loop
+    pub fn spin_up(last: usize) -> Self {
+        Self::new(
+            AnimationClip::still(0),
+            AnimationClip::looping(0, last, 10.0),
+        )
+        .with_startup(AnimationClip::once(0, last, 5.0))
+        .with_random_phase()
+    }
+
+    pub fn with_startup(mut self, startup: AnimationClip) -> Self {
+        self.startup = Some(startup);
+        self
+    }
+
+    // Start somewhere random so rows of the same building don't move in lockstep
+    pub fn with_random_phase(mut self) -> Self {
+        self.phase = rand::random_range(0..self.working.len());
+        self.elapsed = rand::random_range(0.0..1.0 / self.working.fps);
+        self
+    }
+
+    fn clip(&self) -> AnimationClip {
+        match self.state {
+            AnimationState::Idle => self.idle,
+            AnimationState::Starting => self.startup.unwrap_or(self.working),
+            AnimationState::Working => self.working,
+        }
+    }
+
+    fn switch_to(&mut self, state: AnimationState) {
+        self.state = state;
+        self.frame = match state {
+            AnimationState::Working => self.phase % self.working.len(),
+            _ => 0,
+        };
+    }
+
+    pub fn atlas_index(&self) -> usize {
+        self.clip().first + self.frame
+    }
+}
+
+fn animate_buildings(
+    time: Res<Time>,
+    mut q_buildings: Query<(&BuildingStatus, &mut BuildingAnimation, &mut Sprite)>,
+) {
+    for (status, mut animation, mut sprite) in q_buildings.iter_mut() {
+        let working = *status == BuildingStatus::Working;
+        if working {
+            animation.stopped_for = 0.0;
+        } else {
+            animation.stopped_for += time.delta_secs();
+        }
+
+        match (animation.state, working) {
+            (AnimationState::Idle, true) => {
+                let next = if animation.startup.is_some() {
+                    AnimationState::Starting
+                } else {
+                    AnimationState::Working
+                };
+                animation.switch_to(next);
+            }
+            (AnimationState::Starting | AnimationState::Working, false)
+                if animation.stopped_for >= STOP_DELAY =>
+            {
+                animation.switch_to(AnimationState::Idle);
+            }
+            _ => {}
+        }
+
+        let clip = animation.clip();
+        animation.elapsed += time.delta_secs();
+
+        let frame_time = 1.0 / clip.fps;
+        while animation.elapsed >= frame_time {
+            animation.elapsed -= frame_time;
+
+            if animation.frame + 1 < clip.len() {
+                animation.frame += 1;
+            } else if clip.looping {
+                animation.frame = 0;
+            } else if animation.state == AnimationState::Starting {
+                animation.switch_to(AnimationState::Working);
+                break;
+            }
+        }
+
+        let index = animation.atlas_index();
+        if let Some(ref mut atlas) = sprite.texture_atlas
+            && atlas.index != index
+        {
+            atlas.index = index;
+        }
+    }
+}
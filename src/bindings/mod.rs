@@ -0,0 +1,525 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+
+use crate::bindings::routing::{InputRouting, InputRoutingPlugin};
+
+pub mod routing;
+
+const CONFIG_DIR: &str = "config";
+const BINDINGS_FILE: &str = "config/bindings.cfg";
+
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputRoutingPlugin)
+            .insert_resource(KeyBindings::load())
+            .init_resource::<Rebinding>()
+            // After everything else, so the press that picks a key triggers nothing
+            .add_systems(Last, (learn_key_labels, capture_rebinding));
+    }
+}
+
+/// Something the player can do with a key or mouse button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    DragPan,
+    Rotate,
+    Place,
+    CancelPlacement,
+    // Held while placing to skip the material check
+    Plan,
+    ToggleOverlay,
+    TogglePollution,
+    Pause,
+    ToggleConsole,
+    ToolbarSlot(usize),
+}
+
+impl Action {
+    pub const ALL: [Action; 23] = [
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::DragPan,
+        Action::Rotate,
+        Action::Place,
+        Action::CancelPlacement,
+        Action::Plan,
+        Action::ToggleOverlay,
+        Action::TogglePollution,
+        Action::Pause,
+        Action::ToggleConsole,
+        Action::ToolbarSlot(0),
+        Action::ToolbarSlot(1),
+        Action::ToolbarSlot(2),
+        Action::ToolbarSlot(3),
+        Action::ToolbarSlot(4),
+        Action::ToolbarSlot(5),
+        Action::ToolbarSlot(6),
+        Action::ToolbarSlot(7),
+        Action::ToolbarSlot(8),
+        Action::ToolbarSlot(9),
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Action::PanUp => "Pan up".to_string(),
+            Action::PanDown => "Pan down".to_string(),
+            Action::PanLeft => "Pan left".to_string(),
+            Action::PanRight => "Pan right".to_string(),
+            Action::DragPan => "Drag to pan".to_string(),
+            Action::Rotate => "Rotate building".to_string(),
+            Action::Place => "Place building".to_string(),
+            Action::CancelPlacement => "Cancel placement".to_string(),
+            Action::Plan => "Plan without materials".to_string(),
+            Action::ToggleOverlay => "Toggle overlay".to_string(),
+            Action::TogglePollution => "Toggle pollution".to_string(),
+            Action::Pause => "Pause".to_string(),
+            Action::ToggleConsole => "Toggle console".to_string(),
+            Action::ToolbarSlot(slot) => format!("Toolbar slot {}", slot + 1),
+        }
+    }
+
+    // Name in the config file
+    fn id(&self) -> String {
+        match self {
+            Action::PanUp => "pan_up".to_string(),
+            Action::PanDown => "pan_down".to_string(),
+            Action::PanLeft => "pan_left".to_string(),
+            Action::PanRight => "pan_right".to_string(),
+            Action::DragPan => "drag_pan".to_string(),
+            Action::Rotate => "rotate".to_string(),
+            Action::Place => "place".to_string(),
+            Action::CancelPlacement => "cancel_placement".to_string(),
+            Action::Plan => "plan".to_string(),
+            Action::ToggleOverlay => "toggle_overlay".to_string(),
+            Action::TogglePollution => "toggle_pollution".to_string(),
+            Action::Pause => "pause".to_string(),
+            Action::ToggleConsole => "toggle_console".to_string(),
+            Action::ToolbarSlot(slot) => format!("toolbar_slot_{}", slot + 1),
+        }
+    }
+
+    // Still heard while a text field has focus, so the console can be closed from its input line
+    fn works_while_typing(&self) -> bool {
+        matches!(self, Action::ToggleConsole)
+    }
+
+    fn default_binding(&self) -> Binding {
+        match self {
+            Action::PanUp => Binding::Key(KeyCode::KeyW),
+            Action::PanDown => Binding::Key(KeyCode::KeyS),
+            Action::PanLeft => Binding::Key(KeyCode::KeyA),
+            Action::PanRight => Binding::Key(KeyCode::KeyD),
+            Action::DragPan => Binding::Mouse(MouseButton::Middle),
+            Action::Rotate => Binding::Key(KeyCode::KeyR),
+            Action::Place => Binding::Mouse(MouseButton::Left),
+            Action::CancelPlacement => Binding::Mouse(MouseButton::Right),
+            Action::Plan => Binding::Key(KeyCode::ShiftLeft),
+            Action::ToggleOverlay => Binding::Key(KeyCode::KeyO),
+            Action::TogglePollution => Binding::Key(KeyCode::KeyH),
+            Action::Pause => Binding::Key(KeyCode::KeyP),
+            Action::ToggleConsole => Binding::Key(KeyCode::Backquote),
+            Action::ToolbarSlot(slot) => Binding::Key(DIGITS[(slot + 1) % 10]),
+        }
+    }
+}
+
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+// Keys that can be bound, with the name they are saved under. Key codes are
+// physical positions, so the defaults sit in the same place on every layout
+const BINDABLE_KEYS: [(KeyCode, &str); 79] = [
+    (KeyCode::KeyA, "key_a"),
+    (KeyCode::KeyB, "key_b"),
+    (KeyCode::KeyC, "key_c"),
+    (KeyCode::KeyD, "key_d"),
+    (KeyCode::KeyE, "key_e"),
+    (KeyCode::KeyF, "key_f"),
+    (KeyCode::KeyG, "key_g"),
+    (KeyCode::KeyH, "key_h"),
+    (KeyCode::KeyI, "key_i"),
+    (KeyCode::KeyJ, "key_j"),
+    (KeyCode::KeyK, "key_k"),
+    (KeyCode::KeyL, "key_l"),
+    (KeyCode::KeyM, "key_m"),
+    (KeyCode::KeyN, "key_n"),
+    (KeyCode::KeyO, "key_o"),
+    (KeyCode::KeyP, "key_p"),
+    (KeyCode::KeyQ, "key_q"),
+    (KeyCode::KeyR, "key_r"),
+    (KeyCode::KeyS, "key_s"),
+    (KeyCode::KeyT, "key_t"),
+    (KeyCode::KeyU, "key_u"),
+    (KeyCode::KeyV, "key_v"),
+    (KeyCode::KeyW, "key_w"),
+    (KeyCode::KeyX, "key_x"),
+    (KeyCode::KeyY, "key_y"),
+    (KeyCode::KeyZ, "key_z"),
+    (KeyCode::Digit0, "digit_0"),
+    (KeyCode::Digit1, "digit_1"),
+    (KeyCode::Digit2, "digit_2"),
+    (KeyCode::Digit3, "digit_3"),
+    (KeyCode::Digit4, "digit_4"),
+    (KeyCode::Digit5, "digit_5"),
+    (KeyCode::Digit6, "digit_6"),
+    (KeyCode::Digit7, "digit_7"),
+    (KeyCode::Digit8, "digit_8"),
+    (KeyCode::Digit9, "digit_9"),
+    (KeyCode::Numpad0, "numpad_0"),
+    (KeyCode::Numpad1, "numpad_1"),
+    (KeyCode::Numpad2, "numpad_2"),
+    (KeyCode::Numpad3, "numpad_3"),
+    (KeyCode::Numpad4, "numpad_4"),
+    (KeyCode::Numpad5, "numpad_5"),
+    (KeyCode::Numpad6, "numpad_6"),
+    (KeyCode::Numpad7, "numpad_7"),
+    (KeyCode::Numpad8, "numpad_8"),
+    (KeyCode::Numpad9, "numpad_9"),
+    (KeyCode::F1, "f1"),
+    (KeyCode::F2, "f2"),
+    (KeyCode::F3, "f3"),
+    (KeyCode::F4, "f4"),
+    (KeyCode::F5, "f5"),
+    (KeyCode::F6, "f6"),
+    (KeyCode::F7, "f7"),
+    (KeyCode::F8, "f8"),
+    (KeyCode::F9, "f9"),
+    (KeyCode::F10, "f10"),
+    (KeyCode::F11, "f11"),
+    (KeyCode::F12, "f12"),
+    (KeyCode::ArrowUp, "arrow_up"),
+    (KeyCode::ArrowDown, "arrow_down"),
+    (KeyCode::ArrowLeft, "arrow_left"),
+    (KeyCode::ArrowRight, "arrow_right"),
+    (KeyCode::Space, "space"),
+    (KeyCode::Tab, "tab"),
+    (KeyCode::Enter, "enter"),
+    (KeyCode::Backspace, "backspace"),
+    (KeyCode::Delete, "delete"),
+    (KeyCode::ShiftLeft, "shift_left"),
+    (KeyCode::ShiftRight, "shift_right"),
+    (KeyCode::ControlLeft, "control_left"),
+    (KeyCode::ControlRight, "control_right"),
+    (KeyCode::AltLeft, "alt_left"),
+    (KeyCode::AltRight, "alt_right"),
+    (KeyCode::Backquote, "backquote"),
+    (KeyCode::Minus, "minus"),
+    (KeyCode::Equal, "equal"),
+    (KeyCode::BracketLeft, "bracket_left"),
+    (KeyCode::BracketRight, "bracket_right"),
+    (KeyCode::Backslash, "backslash"),
+];
+
+const BINDABLE_BUTTONS: [(MouseButton, &str, &str); 5] = [
+    (MouseButton::Left, "mouse_left", "Left Mouse"),
+    (MouseButton::Right, "mouse_right", "Right Mouse"),
+    (MouseButton::Middle, "mouse_middle", "Middle Mouse"),
+    (MouseButton::Back, "mouse_back", "Back Mouse"),
+    (MouseButton::Forward, "mouse_forward", "Forward Mouse"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    // Name on a QWERTY keyboard, used until the player's layout is known
+    fn label(&self) -> String {
+        match self {
+            Binding::Key(_) => {
+                let id = self.id();
+                let name = id
+                    .strip_prefix("key_")
+                    .or_else(|| id.strip_prefix("digit_"))
+                    .unwrap_or(id);
+                let mut chars = name.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+                    .replace('_', " ")
+            }
+            Binding::Mouse(button) => BINDABLE_BUTTONS
+                .into_iter()
+                .find(|(b, _, _)| b == button)
+                .map_or("Mouse", |(_, _, label)| label)
+                .to_string(),
+        }
+    }
+
+    // Name in the config file
+    fn id(&self) -> &'static str {
+        let id = match self {
+            Binding::Key(key) => BINDABLE_KEYS
+                .into_iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, id)| id),
+            Binding::Mouse(button) => BINDABLE_BUTTONS
+                .into_iter()
+                .find(|(b, _, _)| b == button)
+                .map(|(_, id, _)| id),
+        };
+        id.unwrap_or("unknown")
+    }
+
+    fn from_id(id: &str) -> Option<Binding> {
+        let key = BINDABLE_KEYS
+            .into_iter()
+            .find(|(_, key_id)| *key_id == id)
+            .map(|(key, _)| Binding::Key(key));
+        let button = BINDABLE_BUTTONS
+            .into_iter()
+            .find(|(_, button_id, _)| *button_id == id)
+            .map(|(button, _, _)| Binding::Mouse(button));
+        key.or(button)
+    }
+}
+
+/// Which key or mouse button triggers each action, saved to the config file.
+#[derive(Resource, Clone)]
+pub struct KeyBindings {
+    bindings: HashMap<Action, Binding>,
+    // What each key types on the player's layout, learned as they press them
+    key_labels: HashMap<KeyCode, String>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_binding()))
+                .collect(),
+            key_labels: HashMap::new(),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: Action) -> Binding {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn set(&mut self, action: Action, binding: Binding) {
+        self.bindings.insert(action, binding);
+    }
+
+    /// Short name of the key or button bound to `action`, for menus and tooltips.
+    pub fn label(&self, action: Action) -> String {
+        let binding = self.get(action);
+        match binding {
+            Binding::Key(key) => self
+                .key_labels
+                .get(&key)
+                .cloned()
+                .unwrap_or_else(|| binding.label()),
+            Binding::Mouse(_) => binding.label(),
+        }
+    }
+
+    /// Other actions bound to the same key as `action`.
+    pub fn conflicts(&self, action: Action) -> Vec<Action> {
+        let binding = self.get(action);
+        Action::ALL
+            .into_iter()
+            .filter(|other| *other != action && self.get(*other) == binding)
+            .collect()
+    }
+
+    /// Reads the config file, anything missing or unreadable keeps its default.
+    pub fn load() -> Self {
+        let mut bindings = KeyBindings::default();
+        let Ok(text) = fs::read_to_string(BINDINGS_FILE) else {
+            return bindings;
+        };
+
+        for line in text.lines() {
+            let Some((action, binding)) = line.split_once('=') else {
+                continue;
+            };
+            let (action, binding) = (action.trim(), binding.trim());
+
+            if let Some(key) = action.strip_prefix("label ") {
+                match Binding::from_id(key.trim()) {
+                    Some(Binding::Key(key)) => {
+                        bindings.key_labels.insert(key, binding.to_string());
+                    }
+                    _ => warn!("Ignoring key label '{line}' in {BINDINGS_FILE}"),
+                }
+                continue;
+            }
+
+            let action = Action::ALL.into_iter().find(|a| a.id() == action);
+            match (action, Binding::from_id(binding)) {
+                (Some(action), Some(binding)) => bindings.set(action, binding),
+                _ => warn!("Ignoring key binding '{line}' in {BINDINGS_FILE}"),
+            }
+        }
+
+        bindings
+    }
+
+    pub fn save(&self) {
+        let mut text = String::new();
+        for action in Action::ALL {
+            let _ = writeln!(text, "{} = {}", action.id(), self.get(action).id());
+        }
+        for (key, id) in BINDABLE_KEYS {
+            if let Some(label) = self.key_labels.get(&key) {
+                let _ = writeln!(text, "label {id} = {label}");
+            }
+        }
+
+        if let Err(error) =
+            fs::create_dir_all(CONFIG_DIR).and_then(|_| fs::write(BINDINGS_FILE, text))
+        {
+            warn!("Could not save key bindings: {error}");
+        }
+    }
+}
+
+// Keys are bound by position, so remember what each one types on the player's
+// layout and show that instead of its QWERTY name
+fn learn_key_labels(
+    mut msg_reader: MessageReader<KeyboardInput>,
+    mut bindings: ResMut<KeyBindings>,
+) {
+    let mut learned = false;
+
+    for msg in msg_reader.read() {
+        let Key::Character(text) = &msg.logical_key else {
+            continue;
+        };
+        if msg.state != ButtonState::Pressed || text.chars().any(char::is_control) {
+            continue;
+        }
+
+        // Numpad digits type the same as the number row, their own names tell them apart
+        let Some((key, _)) = BINDABLE_KEYS
+            .into_iter()
+            .find(|(key, id)| *key == msg.key_code && !id.starts_with("numpad_"))
+        else {
+            continue;
+        };
+
+        let label = text.to_uppercase();
+        if bindings.key_labels.get(&key) != Some(&label) {
+            bindings.key_labels.insert(key, label);
+            learned = true;
+        }
+    }
+
+    if learned {
+        bindings.save();
+    }
+}
+
+/// The action waiting for a new key in the controls menu.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<Action>);
+
+// Escape cancels, so it can't be bound itself
+fn capture_rebinding(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<KeyBindings>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::Escape) {
+        rebinding.0 = None;
+        return;
+    }
+
+    let key = keyboard
+        .get_just_pressed()
+        .find(|key| BINDABLE_KEYS.iter().any(|(k, _)| k == *key))
+        .map(|key| Binding::Key(*key));
+    let button = mouse_button
+        .get_just_pressed()
+        .find(|button| BINDABLE_BUTTONS.iter().any(|(b, _, _)| b == *button))
+        .map(|button| Binding::Mouse(*button));
+
+    if let Some(binding) = key.or(button) {
+        bindings.set(action, binding);
+        bindings.save();
+        rebinding.0 = None;
+    }
+}
+
+/// Input read through the player's key bindings, minus whatever egui is using.
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    bindings: Res<'w, KeyBindings>,
+    rebinding: Res<'w, Rebinding>,
+    routing: Res<'w, InputRouting>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse_button: Res<'w, ButtonInput<MouseButton>>,
+}
+
+impl Actions<'_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        if self.rebinding.0.is_some() {
+            return false;
+        }
+
+        match self.bindings.get(action) {
+            Binding::Key(key) => {
+                self.keyboard.pressed(key) && self.routing.key_reaches_world(action)
+            }
+            Binding::Mouse(button) => {
+                self.mouse_button.pressed(button) && self.routing.button_reaches_world(button)
+            }
+        }
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        if self.rebinding.0.is_some() {
+            return false;
+        }
+
+        match self.bindings.get(action) {
+            Binding::Key(key) => {
+                self.keyboard.just_pressed(key) && self.routing.key_reaches_world(action)
+            }
+            Binding::Mouse(button) => {
+                self.mouse_button.just_pressed(button) && self.routing.button_reaches_world(button)
+            }
+        }
+    }
+
+    pub fn label(&self, action: Action) -> String {
+        self.bindings.label(action)
+    }
+}
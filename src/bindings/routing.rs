@@ -0,0 +1,163 @@
+use bevy::input::InputSystems;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass};
+use std::collections::HashSet;
+
+use crate::bindings::Action;
+
+pub struct InputRoutingPlugin;
+
+impl Plugin for InputRoutingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputRouting>()
+            .add_systems(PreUpdate, claim_world_buttons.after(InputSystems))
+            .add_systems(EguiPrimaryContextPass, update_input_routing);
+    }
+}
+
+/// Decides whether input goes to egui or to the world.
+///
+/// egui's interest is read at the end of each UI pass and applies to the
+/// next frame's world systems, the same way egui itself uses its last layout.
+#[derive(Resource, Default)]
+pub struct InputRouting {
+    // The pointer is over a window or egui is dragging one of its widgets
+    pub pointer_over_ui: bool,
+    // A text field has focus
+    pub keyboard_in_ui: bool,
+    // Buttons pressed over the world stay with it until released, so a drag
+    // that wanders over a window keeps going
+    world_buttons: HashSet<MouseButton>,
+}
+
+impl InputRouting {
+    pub fn key_reaches_world(&self, action: Action) -> bool {
+        !self.keyboard_in_ui || action.works_while_typing()
+    }
+
+    pub fn button_reaches_world(&self, button: MouseButton) -> bool {
+        self.world_buttons.contains(&button)
+    }
+
+    // A press belongs to whoever had the pointer when it went down
+    fn claim(&mut self, button: MouseButton) {
+        if !self.pointer_over_ui {
+            self.world_buttons.insert(button);
+        }
+    }
+}
+
+fn claim_world_buttons(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut routing: ResMut<InputRouting>,
+) {
+    routing
+        .world_buttons
+        .retain(|button| mouse_button.pressed(*button));
+
+    for button in mouse_button.get_just_pressed() {
+        routing.claim(*button);
+    }
+}
+
+fn update_input_routing(mut contexts: EguiContexts, mut routing: ResMut<InputRouting>) -> Result {
+    let ctx = contexts.ctx_mut()?;
+
+    routing.pointer_over_ui = ctx.wants_pointer_input();
+    routing.keyboard_in_ui = ctx.wants_keyboard_input();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn world_with_mouse() -> World {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<InputRouting>();
+        world
+    }
+
+    // One frame of input: the press or release, then the routing system, then
+    // the just pressed and just released state is cleared like bevy does
+    fn run_frame(world: &mut World, change: impl FnOnce(&mut ButtonInput<MouseButton>)) {
+        change(&mut world.resource_mut::<ButtonInput<MouseButton>>());
+        world.run_system_once(claim_world_buttons).unwrap();
+        world.resource_mut::<ButtonInput<MouseButton>>().clear();
+    }
+
+    #[test]
+    fn press_over_a_window_stays_with_the_ui() {
+        let mut world = world_with_mouse();
+        world.resource_mut::<InputRouting>().pointer_over_ui = true;
+
+        run_frame(&mut world, |mouse| mouse.press(MouseButton::Left));
+
+        let routing = world.resource::<InputRouting>();
+        assert!(!routing.button_reaches_world(MouseButton::Left));
+    }
+
+    #[test]
+    fn press_over_the_world_is_claimed() {
+        let mut world = world_with_mouse();
+
+        run_frame(&mut world, |mouse| mouse.press(MouseButton::Left));
+
+        let routing = world.resource::<InputRouting>();
+        assert!(routing.button_reaches_world(MouseButton::Left));
+        assert!(!routing.button_reaches_world(MouseButton::Right));
+    }
+
+    #[test]
+    fn drag_over_a_window_keeps_the_button_until_release() {
+        let mut world = world_with_mouse();
+
+        run_frame(&mut world, |mouse| mouse.press(MouseButton::Middle));
+
+        // Still held while the pointer wanders over a window
+        world.resource_mut::<InputRouting>().pointer_over_ui = true;
+        run_frame(&mut world, |_| {});
+        assert!(
+            world
+                .resource::<InputRouting>()
+                .button_reaches_world(MouseButton::Middle)
+        );
+
+        run_frame(&mut world, |mouse| mouse.release(MouseButton::Middle));
+        assert!(
+            !world
+                .resource::<InputRouting>()
+                .button_reaches_world(MouseButton::Middle)
+        );
+    }
+
+    #[test]
+    fn typing_only_lets_the_console_toggle_through() {
+        let routing = InputRouting {
+            keyboard_in_ui: true,
+            ..default()
+        };
+
+        for action in Action::ALL {
+            assert_eq!(
+                routing.key_reaches_world(action),
+                action == Action::ToggleConsole,
+                "{action:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keys_reach_the_world_without_a_focused_text_field() {
+        let routing = InputRouting::default();
+
+        assert!(
+            Action::ALL
+                .iter()
+                .all(|action| routing.key_reaches_world(*action))
+        );
+    }
+}
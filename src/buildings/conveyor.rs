@@ -0,0 +1,303 @@
+use crate::buildings::helpers::{BuildingKind, BuildingRotation, world_to_grid};
+use crate::buildings::registry::{
+    BuildingCategory, BuildingIcon, BuildingPlugin, BuildingType, Facing,
+};
+use crate::items::{Item, ItemAssets, item_sprite};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// Belt lengths an item travels per second
+pub const BELT_SPEED: f32 = 1.5;
+
+// Minimum gap between two items on the same belt, in belt lengths
+pub const ITEM_SPACING: f32 = 0.25;
+
+pub struct ConveyorPlugin;
+
+impl Plugin for ConveyorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BuildingPlugin::<Conveyor>::default())
+            .add_systems(Startup, setup_conveyor)
+            .add_systems(
+                Update,
+                (
+                    update_conveyor_connections,
+                    move_conveyor_items,
+                    position_conveyor_items,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct ConveyorAsset {
+    pub texture: Handle<Image>,
+    pub atlas_layout: Handle<TextureAtlasLayout>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BeltItem {
+    pub item: Item,
+    pub entity: Entity,
+    // 0.0 at the belt's entry edge, 1.0 at its exit edge
+    pub progress: f32,
+}
+
+#[derive(Component)]
+pub struct Conveyor {
+    // Front-most item first
+    pub items: Vec<BeltItem>,
+    // Direction from the belt center to the edge items come in through
+    pub entry: BuildingRotation,
+}
+
+impl Conveyor {
+    pub fn new(rotation: BuildingRotation) -> Self {
+        Self {
+            items: Vec::new(),
+            entry: rotation.opposite(),
+        }
+    }
+
+    pub fn can_insert(&self, progress: f32) -> bool {
+        self.items
+            .iter()
+            .all(|belt_item| (belt_item.progress - progress).abs() >= ITEM_SPACING)
+    }
+
+    pub fn insert(&mut self, belt_item: BeltItem) {
+        let index = self
+            .items
+            .iter()
+            .position(|other| other.progress < belt_item.progress)
+            .unwrap_or(self.items.len());
+        self.items.insert(index, belt_item);
+    }
+}
+
+impl BuildingType for Conveyor {
+    const KIND: BuildingKind = BuildingKind::Conveyor;
+    const CATEGORY: BuildingCategory = BuildingCategory::Logistics;
+    const ICON: BuildingIcon = BuildingIcon::still("textures/conveyor.png", 3, 0);
+    const FACING: Facing = Facing::Sprite;
+
+    type Assets = ConveyorAsset;
+
+    fn sprite(assets: &ConveyorAsset) -> Sprite {
+        Sprite {
+            image: assets.texture.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: assets.atlas_layout.clone(),
+                index: 0,
+            }),
+            ..default()
+        }
+    }
+
+    fn components(rotation: BuildingRotation) -> impl Bundle {
+        Conveyor::new(rotation)
+    }
+}
+
+/// Sprite of an item riding a belt, a child of that belt.
+#[derive(Component)]
+pub struct BeltItemSprite;
+
+fn setup_conveyor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let texture = asset_server.load("textures/conveyor.png");
+
+    // Create texture atlas layout for 96x32 spritesheet with straight, right and left corner frames
+    let layout = TextureAtlasLayout::from_grid(
+        UVec2::new(32, 32), // Size of each frame
+        3,                  // Number of columns
+        1,                  // Number of rows
+        None,
+        None,
+    );
+    let atlas_layout = texture_atlases.add(layout);
+
+    commands.insert_resource(ConveyorAsset {
+        texture,
+        atlas_layout,
+    });
+}
+
+pub fn spawn_belt_item(
+    commands: &mut Commands,
+    item_assets: &ItemAssets,
+    item: Item,
+    belt: Entity,
+) -> Entity {
+    commands
+        .spawn((
+            BeltItemSprite,
+            item_sprite(item_assets, item),
+            Pickable::IGNORE,
+            Transform::from_xyz(0.0, 0.0, 1.0),
+            ChildOf(belt),
+        ))
+        .id()
+}
+
+fn update_conveyor_connections(
+    mut q_conveyors: Query<(
+        &mut Transform,
+        &mut Sprite,
+        &BuildingRotation,
+        &mut Conveyor,
+    )>,
+) {
+    // Map grid positions to belt directions so we can look up neighbors
+    let mut belt_rotations: HashMap<(i32, i32), BuildingRotation> = HashMap::new();
+    for (transform, _, rotation, _) in q_conveyors.iter() {
+        belt_rotations.insert(world_to_grid(transform.translation), *rotation);
+    }
+
+    for (mut transform, mut sprite, rotation, mut conveyor) in q_conveyors.iter_mut() {
+        let (x, y) = world_to_grid(transform.translation);
+
+        // A neighbor feeds this belt if it points straight at it
+        let feeds_from = |side: BuildingRotation| -> bool {
+            let (dx, dy) = side.to_grid_offset();
+            belt_rotations
+                .get(&(x + dx, y + dy))
+                .map(|neighbor| *neighbor == side.opposite())
+                .unwrap_or(false)
+        };
+
+        let mut right = *rotation;
+        right.rotate_clockwise();
+        let left = right.opposite();
+
+        let from_back = feeds_from(rotation.opposite());
+        let from_right = feeds_from(right);
+        let from_left = feeds_from(left);
+
+        // Only turn when a single side feeds the belt and nothing comes from behind
+        let (texture_index, entry) = if !from_back && from_right && !from_left {
+            (1, right)
+        } else if !from_back && from_left && !from_right {
+            (2, left)
+        } else {
+            (0, rotation.opposite())
+        };
+
+        conveyor.entry = entry;
+
+        if let Some(ref mut atlas) = sprite.texture_atlas {
+            atlas.index = texture_index;
+        }
+        transform.rotation = Quat::from_rotation_z(rotation.to_radians());
+    }
+}
+
+fn move_conveyor_items(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_conveyors: Query<(Entity, &Transform, &BuildingRotation, &mut Conveyor)>,
+) {
+    let step = BELT_SPEED * time.delta_secs();
+
+    let mut belts: HashMap<(i32, i32), Entity> = HashMap::new();
+    for (entity, transform, _, _) in q_conveyors.iter() {
+        belts.insert(world_to_grid(transform.translation), entity);
+    }
+
+    // Move items along each belt, never closer than the spacing to the one ahead
+    let mut handoffs: Vec<(Entity, Entity, BuildingRotation)> = Vec::new();
+    for (entity, transform, rotation, mut conveyor) in q_conveyors.iter_mut() {
+        let mut limit = 1.0;
+        for belt_item in conveyor.items.iter_mut() {
+            belt_item.progress = (belt_item.progress + step)
+                .min(limit)
+                .max(belt_item.progress);
+            limit = belt_item.progress - ITEM_SPACING;
+        }
+
+        let at_end = conveyor
+            .items
+            .first()
+            .is_some_and(|belt_item| belt_item.progress >= 1.0);
+
+        if at_end {
+            let (x, y) = world_to_grid(transform.translation);
+            let (dx, dy) = rotation.to_grid_offset();
+            if let Some(next) = belts.get(&(x + dx, y + dy)) {
+                handoffs.push((entity, *next, *rotation));
+            }
+        }
+    }
+
+    // Then pass the front items on to the next belt if there is room
+    for (from, to, direction) in handoffs {
+        let Ok((_, _, to_rotation, to_conveyor)) = q_conveyors.get(to) else {
+            continue;
+        };
+
+        // Belts facing each other never exchange items
+        if *to_rotation == direction.opposite() {
+            continue;
+        }
+
+        // Items coming in from the side land in the middle of the belt
+        let progress = if to_conveyor.entry == direction.opposite() {
+            0.0
+        } else {
+            0.5
+        };
+
+        if !to_conveyor.can_insert(progress) {
+            continue;
+        }
+
+        let mut belt_item = {
+            let Ok((_, _, _, mut from_conveyor)) = q_conveyors.get_mut(from) else {
+                continue;
+            };
+            if from_conveyor.items.is_empty() {
+                continue;
+            }
+            from_conveyor.items.remove(0)
+        };
+        belt_item.progress = progress;
+
+        if let Ok((_, _, _, mut to_conveyor)) = q_conveyors.get_mut(to) {
+            to_conveyor.insert(belt_item);
+            commands.entity(belt_item.entity).insert(ChildOf(to));
+        }
+    }
+}
+
+fn position_conveyor_items(
+    q_conveyors: Query<(&Transform, &BuildingRotation, &Conveyor)>,
+    mut q_items: Query<&mut Transform, (With<BeltItemSprite>, Without<Conveyor>)>,
+) {
+    for (transform, rotation, conveyor) in q_conveyors.iter() {
+        // Items travel from the entry edge to the center, then on to the exit edge
+        let entry = conveyor.entry.to_direction() * 16.0;
+        let exit = rotation.to_direction() * 16.0;
+
+        // Item sprites are children of the rotated belt, so undo its rotation
+        let inverse = transform.rotation.inverse();
+
+        for belt_item in conveyor.items.iter() {
+            let Ok(mut item_transform) = q_items.get_mut(belt_item.entity) else {
+                continue;
+            };
+
+            let offset = if belt_item.progress < 0.5 {
+                entry * (1.0 - belt_item.progress * 2.0)
+            } else {
+                exit * (belt_item.progress * 2.0 - 1.0)
+            };
+
+            item_transform.translation = inverse * offset.extend(0.0) + Vec3::Z;
+            item_transform.rotation = inverse;
+        }
+    }
+}
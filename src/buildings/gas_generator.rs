@@ -0,0 +1,102 @@
+use crate::buildings::helpers::{BuildingKind, BuildingRotation, BuildingStatus};
+use crate::buildings::registry::{
+    BuildingCategory, BuildingIcon, BuildingPlugin, BuildingType, Facing,
+};
+use crate::fluids::{Fluid, FluidPort, FluidPorts, FluidTank, FluidTanks, PortFlow};
+use crate::pollution::PollutionEmitter;
+use crate::power::PowerProducer;
+use crate::stats::{Material, ProductionStats};
+use bevy::prelude::*;
+
+// kW at full load
+const MAX_OUTPUT: f32 = 500.0;
+
+// Petroleum gas burned per second at full load
+const GAS_PER_SECOND: f32 = 5.0;
+
+// Pollution per second while burning gas
+const POLLUTION: f32 = 3.0;
+
+pub struct GasGeneratorPlugin;
+
+impl Plugin for GasGeneratorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BuildingPlugin::<GasGenerator>::default())
+            .add_systems(Startup, setup_gas_generator)
+            .add_systems(Update, burn_generator_fuel);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct GasGeneratorAsset {
+    pub texture: Handle<Image>,
+}
+
+#[derive(Component)]
+pub struct GasGenerator;
+
+impl BuildingType for GasGenerator {
+    const KIND: BuildingKind = BuildingKind::GasGenerator;
+    const CATEGORY: BuildingCategory = BuildingCategory::Processing;
+    const ICON: BuildingIcon = BuildingIcon::still("textures/gas_generator.png", 1, 0);
+    const FACING: Facing = Facing::Indicator(8.0);
+
+    type Assets = GasGeneratorAsset;
+
+    fn sprite(assets: &GasGeneratorAsset) -> Sprite {
+        Sprite {
+            image: assets.texture.clone(),
+            ..default()
+        }
+    }
+
+    fn components(_: BuildingRotation) -> impl Bundle {
+        (
+            GasGenerator,
+            BuildingStatus::default(),
+            PowerProducer::new(MAX_OUTPUT),
+            PollutionEmitter(POLLUTION),
+            FluidTanks(vec![FluidTank::new(100.0).with_filter(Fluid::PetroleumGas)]),
+            // Gas comes in through the side the rotation indicator points at
+            FluidPorts(vec![FluidPort::new(Vec2::X, PortFlow::Input, 0)]),
+        )
+    }
+}
+
+fn setup_gas_generator(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let texture = asset_server.load("textures/gas_generator.png");
+
+    commands.insert_resource(GasGeneratorAsset { texture });
+}
+
+fn burn_generator_fuel(
+    time: Res<Time>,
+    mut stats: ResMut<ProductionStats>,
+    mut q_generators: Query<
+        (&mut FluidTanks, &mut PowerProducer, &mut BuildingStatus),
+        With<GasGenerator>,
+    >,
+) {
+    for (mut tanks, mut producer, mut status) in q_generators.iter_mut() {
+        let Some(tank) = tanks.0.get_mut(0) else {
+            continue;
+        };
+
+        // Burn for whatever the grid asked of us last frame
+        let burned = tank.take(GAS_PER_SECOND * producer.load() * time.delta_secs());
+        stats.consumed(
+            Material::Fluid(Fluid::PetroleumGas),
+            BuildingKind::GasGenerator,
+            burned,
+        );
+        producer.fueled = !tank.is_empty();
+
+        *status = if !producer.fueled {
+            BuildingStatus::InputStarved
+        } else if burned > 0.0 {
+            BuildingStatus::Working
+        } else {
+            BuildingStatus::Idle
+        };
+    }
+}
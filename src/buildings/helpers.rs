@@ -1,8 +1,172 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::picking::hover::PickingInteraction;
 use bevy::prelude::*;
 
+use crate::bindings::routing::InputRouting;
+use crate::bindings::{Action, Actions};
+use crate::construction::{ConstructionJob, ConstructionQueue, spawn_construction_site};
+use crate::fluids::Fluid;
+use crate::items::Item;
+use crate::stats::Material;
+
+pub const TILE_SIZE: f32 = 32.0;
+
+// Buildings and their previews are drawn above the tilemap
+pub const BUILDING_Z: f32 = 10.0;
+
 #[derive(Component)]
 pub struct Building;
 
+/// Which kind of building an entity is, for anything that treats them all alike.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BuildingKind {
+    Pipe,
+    OilExtractor,
+    SmallOilContainer,
+    MediumOilContainer,
+    LargeOilContainer,
+    OilRefinery,
+    Pump,
+    CheckValve,
+    OverflowValve,
+    Conveyor,
+    Inserter,
+    PowerPole,
+    GasGenerator,
+}
+
+impl BuildingKind {
+    pub const ALL: [BuildingKind; 13] = [
+        BuildingKind::Pipe,
+        BuildingKind::OilExtractor,
+        BuildingKind::SmallOilContainer,
+        BuildingKind::MediumOilContainer,
+        BuildingKind::LargeOilContainer,
+        BuildingKind::OilRefinery,
+        BuildingKind::Pump,
+        BuildingKind::CheckValve,
+        BuildingKind::OverflowValve,
+        BuildingKind::Conveyor,
+        BuildingKind::Inserter,
+        BuildingKind::PowerPole,
+        BuildingKind::GasGenerator,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildingKind::Pipe => "Pipe",
+            BuildingKind::OilExtractor => "Oil Extractor",
+            BuildingKind::SmallOilContainer => "Small Oil Container",
+            BuildingKind::MediumOilContainer => "Medium Oil Container",
+            BuildingKind::LargeOilContainer => "Large Oil Container",
+            BuildingKind::OilRefinery => "Oil Refinery",
+            BuildingKind::Pump => "Pump",
+            BuildingKind::CheckValve => "Check Valve",
+            BuildingKind::OverflowValve => "Overflow Valve",
+            BuildingKind::Conveyor => "Conveyor Belt",
+            BuildingKind::Inserter => "Inserter",
+            BuildingKind::PowerPole => "Power Pole",
+            BuildingKind::GasGenerator => "Gas Generator",
+        }
+    }
+
+    /// Name used by console commands and save files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            BuildingKind::Pipe => "pipe",
+            BuildingKind::OilExtractor => "oil_extractor",
+            BuildingKind::SmallOilContainer => "small_oil_container",
+            BuildingKind::MediumOilContainer => "medium_oil_container",
+            BuildingKind::LargeOilContainer => "large_oil_container",
+            BuildingKind::OilRefinery => "oil_refinery",
+            BuildingKind::Pump => "pump",
+            BuildingKind::CheckValve => "check_valve",
+            BuildingKind::OverflowValve => "overflow_valve",
+            BuildingKind::Conveyor => "conveyor",
+            BuildingKind::Inserter => "inserter",
+            BuildingKind::PowerPole => "power_pole",
+            BuildingKind::GasGenerator => "gas_generator",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<BuildingKind> {
+        BuildingKind::ALL.into_iter().find(|kind| kind.id() == id)
+    }
+
+    /// Width and height in tiles, matches the `Footprint` the building is spawned with.
+    pub fn footprint(&self) -> i32 {
+        match self {
+            BuildingKind::OilRefinery => 2,
+            _ => 1,
+        }
+    }
+
+    /// Seconds a construction site takes to finish, deconstruction takes half.
+    pub fn build_time(&self) -> f32 {
+        match self {
+            BuildingKind::Pipe | BuildingKind::Conveyor => 0.5,
+            BuildingKind::Inserter | BuildingKind::PowerPole => 1.0,
+            BuildingKind::CheckValve | BuildingKind::OverflowValve => 1.5,
+            BuildingKind::Pump | BuildingKind::SmallOilContainer => 2.0,
+            BuildingKind::OilExtractor | BuildingKind::MediumOilContainer => 3.0,
+            BuildingKind::LargeOilContainer | BuildingKind::GasGenerator => 4.0,
+            BuildingKind::OilRefinery => 8.0,
+        }
+    }
+
+    /// What it takes to build one, refunded in full when it is deleted.
+    pub fn cost(&self) -> &'static [(Material, f32)] {
+        const CRUDE: Material = Material::Fluid(Fluid::CrudeOil);
+        const LIGHT_OIL: Material = Material::Fluid(Fluid::LightOil);
+        const PLASTIC: Material = Material::Item(Item::Plastic);
+        const SULFUR: Material = Material::Item(Item::Sulfur);
+
+        match self {
+            BuildingKind::Pipe => &[(CRUDE, 5.0)],
+            BuildingKind::OilExtractor => &[(CRUDE, 50.0)],
+            BuildingKind::SmallOilContainer => &[(CRUDE, 40.0)],
+            BuildingKind::MediumOilContainer => &[(CRUDE, 100.0)],
+            BuildingKind::LargeOilContainer => &[(CRUDE, 150.0), (LIGHT_OIL, 100.0)],
+            BuildingKind::OilRefinery => &[(CRUDE, 300.0)],
+            BuildingKind::Pump => &[(CRUDE, 40.0), (LIGHT_OIL, 20.0)],
+            BuildingKind::CheckValve | BuildingKind::OverflowValve => &[(CRUDE, 30.0)],
+            BuildingKind::Conveyor => &[(PLASTIC, 1.0)],
+            BuildingKind::Inserter => &[(PLASTIC, 2.0), (SULFUR, 1.0)],
+            BuildingKind::PowerPole => &[(LIGHT_OIL, 10.0)],
+            BuildingKind::GasGenerator => &[(CRUDE, 100.0), (LIGHT_OIL, 50.0)],
+        }
+    }
+}
+
+/// What a production building did on its last update, for overlays and alerts.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BuildingStatus {
+    #[default]
+    Idle,
+    Working,
+    // Finished products have nowhere to go
+    OutputBlocked,
+    // Waiting on inputs
+    InputStarved,
+    // Nothing left in the ground to extract
+    Depleted,
+    // Built where there was never anything to extract
+    NoDeposit,
+}
+
+impl BuildingStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildingStatus::Idle => "Idle",
+            BuildingStatus::Working => "Working",
+            BuildingStatus::OutputBlocked => "Output blocked",
+            BuildingStatus::InputStarved => "Input starved",
+            BuildingStatus::Depleted => "Depleted",
+            BuildingStatus::NoDeposit => "No deposit",
+        }
+    }
+}
+
 pub fn snap_to_grid(world_pos: Vec2, tile_size: f32) -> Vec2 {
     Vec2::new(
         (world_pos.x / tile_size).floor() * tile_size + tile_size / 2.0,
@@ -10,19 +174,98 @@ pub fn snap_to_grid(world_pos: Vec2, tile_size: f32) -> Vec2 {
     )
 }
 
+// Floor rather than round, rounding tile centers sends -0.5 and 0.5 to
+// cells two apart and breaks adjacency across the origin
 pub fn world_to_grid(world_pos: Vec3) -> (i32, i32) {
-    let tile_size = 32.0;
     (
-        (world_pos.x / tile_size).round() as i32,
-        (world_pos.y / tile_size).round() as i32,
+        (world_pos.x / TILE_SIZE).floor() as i32,
+        (world_pos.y / TILE_SIZE).floor() as i32,
     )
 }
 
+/// Center of a building covering `tile`, the inverse of `world_to_grid`.
+pub fn grid_to_world(tile: (i32, i32), size: i32) -> Vec2 {
+    let corner = Vec2::new(tile.0 as f32, tile.1 as f32) * TILE_SIZE;
+
+    // Even sized buildings are centered on the tile's bottom left corner
+    if size % 2 == 0 {
+        corner
+    } else {
+        corner + Vec2::splat(TILE_SIZE / 2.0)
+    }
+}
+
+/// Width and height of a building in tiles. Buildings without one take up a single tile.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Footprint(pub i32);
+
+pub fn footprint_tiles(center: Vec3, size: i32) -> Vec<(i32, i32)> {
+    // Even sized buildings are centered on a tile corner, so step out from the
+    // center to the middle of each covered tile
+    let half = (size as f32 - 1.0) / 2.0;
+    let mut tiles = Vec::new();
+
+    for i in 0..size {
+        for j in 0..size {
+            let offset = Vec2::new(i as f32 - half, j as f32 - half) * TILE_SIZE;
+            tiles.push(world_to_grid((center.truncate() + offset).extend(0.0)));
+        }
+    }
+
+    tiles
+}
+
+/// Drops whatever preview is following the cursor and leaves delete mode.
+#[derive(Message)]
+pub struct CancelPlacementMsg;
+
+// Escape always backs out, on top of the bindable cancel button
+pub fn cancel_placement_input(
+    actions: Actions,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    routing: Res<InputRouting>,
+    mut msg_writer: MessageWriter<CancelPlacementMsg>,
+) {
+    let escape = keyboard.just_pressed(KeyCode::Escape) && !routing.keyboard_in_ui;
+
+    if escape || actions.just_pressed(Action::CancelPlacement) {
+        msg_writer.write(CancelPlacementMsg);
+    }
+}
+
+// Sweeping up this many buildings asks before tearing them down
+const DELETE_CONFIRM_THRESHOLD: usize = 10;
+
+// Laid over buildings that would go if the button were released now, as a
+// child sprite so systems that color the building itself don't wipe it
+const DELETE_TINT: Color = Color::srgba(1.0, 0.2, 0.2, 0.5);
+const DELETE_TINT_Z: f32 = 3.0;
+
 #[derive(Resource, Default)]
 pub struct DeleteMode {
     pub active: bool,
+    // Buildings swept over by the current drag, or waiting on confirmation
+    pub selection: Vec<Entity>,
+    // The selection is big enough that the toolbar asks first
+    pub confirming: bool,
+    // The selection is ready to be deconstructed
+    pub confirmed: bool,
+    // Under the pointer right now, highlighted even before a drag starts
+    hovered: Vec<Entity>,
+}
+
+impl DeleteMode {
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+        self.confirming = false;
+        self.confirmed = false;
+    }
 }
 
+/// The red highlight child delete mode put over a building.
+#[derive(Component)]
+pub struct DeleteTint(Entity);
+
 pub fn check_if_clicked_building<'a>(
     mut msg_reader: MessageReader<Pointer<Click>>,
     q_buildings: Query<&Building>,
@@ -35,19 +278,138 @@ pub fn check_if_clicked_building<'a>(
     None
 }
 
-pub fn delete_clicked_building(
-    mut commands: Commands,
-    mut msg_reader: MessageReader<Pointer<Click>>,
-    q_buildings: Query<&Building>,
-    delete_mode: Res<DeleteMode>,
+// Dragging with the place button sweeps up every building the pointer
+// crosses, letting go deletes them or asks first when there are many
+pub fn select_buildings_to_delete(
+    actions: Actions,
+    mut msg_reader: MessageReader<CancelPlacementMsg>,
+    mut delete_mode: ResMut<DeleteMode>,
+    routing: Res<InputRouting>,
+    q_interactions: Query<(Entity, &PickingInteraction, Option<&ChildOf>)>,
+    q_buildings: Query<(), With<Building>>,
 ) {
+    // Cancelling backs out of delete mode the same way it backs out of placing
+    if msg_reader.read().count() > 0 {
+        delete_mode.active = false;
+    }
+
     if !delete_mode.active {
+        if !delete_mode.selection.is_empty() || !delete_mode.hovered.is_empty() {
+            delete_mode.clear_selection();
+            delete_mode.hovered.clear();
+        }
         return;
     }
 
-    for msg in msg_reader.read() {
-        if q_buildings.contains(msg.entity) {
-            commands.entity(msg.entity).despawn();
+    // The picking backend hovers sprites, rotation indicators stand for their building
+    delete_mode.hovered = q_interactions
+        .iter()
+        .filter(|_| !routing.pointer_over_ui)
+        .filter(|(_, interaction, _)| **interaction != PickingInteraction::None)
+        .filter_map(|(entity, _, child_of)| {
+            [Some(entity), child_of.map(|c| c.parent())]
+                .into_iter()
+                .flatten()
+                .find(|candidate| q_buildings.contains(*candidate))
+        })
+        .collect();
+
+    if delete_mode.confirming || delete_mode.confirmed {
+        return;
+    }
+
+    if actions.pressed(Action::Place) {
+        for building in delete_mode.hovered.clone() {
+            if !delete_mode.selection.contains(&building) {
+                delete_mode.selection.push(building);
+            }
+        }
+    } else if delete_mode.selection.len() >= DELETE_CONFIRM_THRESHOLD {
+        delete_mode.confirming = true;
+    } else if !delete_mode.selection.is_empty() {
+        delete_mode.confirmed = true;
+    }
+}
+
+// Buildings are not removed right away, they are handed to a deconstruction
+// site that refunds them once it finishes
+pub fn delete_selected_buildings(
+    mut commands: Commands,
+    mut delete_mode: ResMut<DeleteMode>,
+    mut queue: ResMut<ConstructionQueue>,
+    q_buildings: Query<
+        (
+            &BuildingKind,
+            &Sprite,
+            &Transform,
+            Option<&Footprint>,
+            Option<&DeleteTint>,
+        ),
+        With<Building>,
+    >,
+) {
+    if !delete_mode.confirmed {
+        return;
+    }
+    delete_mode.confirmed = false;
+
+    for building in std::mem::take(&mut delete_mode.selection) {
+        let Ok((kind, sprite, transform, footprint, tint)) = q_buildings.get(building) else {
+            continue;
+        };
+
+        if let Some(tint) = tint {
+            commands.entity(tint.0).despawn();
+            commands.entity(building).remove::<DeleteTint>();
+        }
+
+        let ghost = spawn_construction_site(
+            &mut commands,
+            building,
+            *kind,
+            ConstructionJob::Deconstruct,
+            sprite,
+            *transform,
+            footprint.map(|f| f.0).unwrap_or(1),
+        );
+        queue.0.push(ghost);
+
+        commands.entity(building).insert(Disabled);
+    }
+}
+
+pub fn highlight_buildings_to_delete(
+    mut commands: Commands,
+    delete_mode: Res<DeleteMode>,
+    q_buildings: Query<(Entity, Option<&Footprint>, Option<&DeleteTint>), With<Building>>,
+) {
+    for (building, footprint, tint) in q_buildings.iter() {
+        let marked = delete_mode.active
+            && (delete_mode.hovered.contains(&building)
+                || delete_mode.selection.contains(&building));
+
+        match (marked, tint) {
+            (true, None) => {
+                let size = footprint.map(|f| f.0).unwrap_or(1) as f32 * TILE_SIZE;
+                let highlight = commands
+                    .spawn((
+                        Sprite {
+                            color: DELETE_TINT,
+                            custom_size: Some(Vec2::splat(size)),
+                            ..default()
+                        },
+                        Transform::from_xyz(0.0, 0.0, DELETE_TINT_Z),
+                        Pickable::IGNORE,
+                        ChildOf(building),
+                    ))
+                    .id();
+                commands.entity(building).insert(DeleteTint(highlight));
+            }
+            (false, Some(tint)) => {
+                commands.entity(tint.0).despawn();
+                commands.entity(building).remove::<DeleteTint>();
+            }
+            _ => {}
         }
     }
 }
@@ -63,6 +425,37 @@ pub enum BuildingRotation {
 }
 
 impl BuildingRotation {
+    pub const ALL: [BuildingRotation; 4] = [
+        BuildingRotation::North,
+        BuildingRotation::East,
+        BuildingRotation::South,
+        BuildingRotation::West,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildingRotation::North => "North",
+            BuildingRotation::East => "East",
+            BuildingRotation::South => "South",
+            BuildingRotation::West => "West",
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            BuildingRotation::North => "north",
+            BuildingRotation::East => "east",
+            BuildingRotation::South => "south",
+            BuildingRotation::West => "west",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<BuildingRotation> {
+        BuildingRotation::ALL
+            .into_iter()
+            .find(|rotation| rotation.id() == id)
+    }
+
     pub fn rotate_clockwise(&mut self) {
         *self = match self {
             BuildingRotation::North => BuildingRotation::East,
@@ -81,6 +474,38 @@ impl BuildingRotation {
         }
     }
 
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, BuildingRotation::East | BuildingRotation::West)
+    }
+
+    // Rotates an offset given for an East facing building
+    pub fn rotate_vec(&self, v: Vec2) -> Vec2 {
+        match self {
+            BuildingRotation::North => Vec2::new(-v.y, v.x),
+            BuildingRotation::East => v,
+            BuildingRotation::South => Vec2::new(v.y, -v.x),
+            BuildingRotation::West => Vec2::new(-v.x, -v.y),
+        }
+    }
+
+    pub fn opposite(&self) -> BuildingRotation {
+        match self {
+            BuildingRotation::North => BuildingRotation::South,
+            BuildingRotation::East => BuildingRotation::West,
+            BuildingRotation::South => BuildingRotation::North,
+            BuildingRotation::West => BuildingRotation::East,
+        }
+    }
+
+    pub fn to_grid_offset(&self) -> (i32, i32) {
+        match self {
+            BuildingRotation::North => (0, 1),
+            BuildingRotation::East => (1, 0),
+            BuildingRotation::South => (0, -1),
+            BuildingRotation::West => (-1, 0),
+        }
+    }
+
     pub fn to_direction(&self) -> Vec2 {
         match self {
             BuildingRotation::North => Vec2::new(0.0, 1.0),
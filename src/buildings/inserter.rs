@@ -0,0 +1,158 @@
+use crate::buildings::conveyor::{BeltItem, Conveyor, spawn_belt_item};
+use crate::buildings::helpers::{
+    BuildingKind, BuildingRotation, BuildingStatus, Footprint, footprint_tiles, world_to_grid,
+};
+use crate::buildings::registry::{
+    BuildingCategory, BuildingIcon, BuildingPlugin, BuildingType, Facing,
+};
+use crate::items::{Item, ItemAssets, ItemStorage};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// Seconds for each half of a swing, so one item moves every two of these
+const SWING_TIME: f32 = 0.5;
+
+pub struct InserterPlugin;
+
+impl Plugin for InserterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BuildingPlugin::<Inserter>::default())
+            .add_systems(Startup, setup_inserter)
+            .add_systems(Update, run_inserters);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct InserterAsset {
+    pub texture: Handle<Image>,
+}
+
+/// Picks items up from the tile behind it and drops them on the tile it faces.
+#[derive(Component)]
+pub struct Inserter {
+    pub held: Option<Item>,
+    pub timer: Timer,
+}
+
+impl Default for Inserter {
+    fn default() -> Self {
+        Self {
+            held: None,
+            timer: Timer::from_seconds(SWING_TIME, TimerMode::Repeating),
+        }
+    }
+}
+
+impl BuildingType for Inserter {
+    const KIND: BuildingKind = BuildingKind::Inserter;
+    const CATEGORY: BuildingCategory = BuildingCategory::Logistics;
+    const ICON: BuildingIcon = BuildingIcon::still("textures/inserter.png", 1, 0);
+    const FACING: Facing = Facing::Sprite;
+
+    type Assets = InserterAsset;
+
+    fn sprite(assets: &InserterAsset) -> Sprite {
+        Sprite {
+            image: assets.texture.clone(),
+            ..default()
+        }
+    }
+
+    fn components(_: BuildingRotation) -> impl Bundle {
+        (Inserter::default(), BuildingStatus::default())
+    }
+}
+
+fn setup_inserter(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let texture = asset_server.load("textures/inserter.png");
+
+    commands.insert_resource(InserterAsset { texture });
+}
+
+fn run_inserters(
+    mut commands: Commands,
+    time: Res<Time>,
+    item_assets: Res<ItemAssets>,
+    mut q_inserters: Query<(
+        &Transform,
+        &BuildingRotation,
+        &mut Inserter,
+        &mut BuildingStatus,
+    )>,
+    mut q_conveyors: Query<(Entity, &Transform, &mut Conveyor)>,
+    mut q_storages: Query<(Entity, &Transform, Option<&Footprint>, &mut ItemStorage)>,
+) {
+    let mut belts: HashMap<(i32, i32), Entity> = HashMap::new();
+    for (entity, transform, _) in q_conveyors.iter() {
+        belts.insert(world_to_grid(transform.translation), entity);
+    }
+
+    // Buildings can cover more than one tile, map all of them
+    let mut storages: HashMap<(i32, i32), Entity> = HashMap::new();
+    for (entity, transform, footprint, _) in q_storages.iter() {
+        let size = footprint.map(|f| f.0).unwrap_or(1);
+        for tile in footprint_tiles(transform.translation, size) {
+            storages.insert(tile, entity);
+        }
+    }
+
+    for (transform, rotation, mut inserter, mut status) in q_inserters.iter_mut() {
+        inserter.timer.tick(time.delta());
+        if !inserter.timer.just_finished() {
+            continue;
+        }
+
+        let (x, y) = world_to_grid(transform.translation);
+        let (dx, dy) = rotation.to_grid_offset();
+        let source = (x - dx, y - dy);
+        let target = (x + dx, y + dy);
+
+        let held_before = inserter.held;
+
+        match inserter.held {
+            None => {
+                if let Some(belt) = belts.get(&source) {
+                    if let Ok((_, _, mut conveyor)) = q_conveyors.get_mut(*belt)
+                        && !conveyor.items.is_empty()
+                    {
+                        let belt_item = conveyor.items.remove(0);
+                        commands.entity(belt_item.entity).despawn();
+                        inserter.held = Some(belt_item.item);
+                    }
+                } else if let Some(building) = storages.get(&source)
+                    && let Ok((_, _, _, mut storage)) = q_storages.get_mut(*building)
+                {
+                    inserter.held = storage.take_any();
+                }
+            }
+            Some(item) => {
+                if let Some(belt) = belts.get(&target) {
+                    if let Ok((_, _, mut conveyor)) = q_conveyors.get_mut(*belt)
+                        && conveyor.can_insert(0.5)
+                    {
+                        let entity = spawn_belt_item(&mut commands, &item_assets, item, *belt);
+                        conveyor.insert(BeltItem {
+                            item,
+                            entity,
+                            progress: 0.5,
+                        });
+                        inserter.held = None;
+                    }
+                } else if let Some(building) = storages.get(&target)
+                    && let Ok((_, _, _, mut storage)) = q_storages.get_mut(*building)
+                    && storage.insert(item)
+                {
+                    inserter.held = None;
+                }
+            }
+        }
+
+        *status = if inserter.held != held_before {
+            BuildingStatus::Working
+        } else if inserter.held.is_none() {
+            BuildingStatus::InputStarved
+        } else {
+            BuildingStatus::OutputBlocked
+        };
+    }
+}
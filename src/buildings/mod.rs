@@ -1,5 +1,13 @@
+pub mod conveyor;
+pub mod gas_generator;
 pub mod helpers;
+pub mod inserter;
 pub mod oil_container;
 pub mod oil_extractor;
 pub mod oil_refinery;
 pub mod pipe;
+pub mod placement;
+pub mod power_pole;
+pub mod pump;
+pub mod registry;
+pub mod valve;
@@ -1,68 +1,104 @@
-use crate::buildings::helpers::{Building, BuildingRotation, snap_to_grid};
+use crate::animation::BuildingAnimation;
+use crate::buildings::helpers::{BuildingKind, BuildingRotation, BuildingStatus};
+use crate::buildings::registry::{
+    BuildingCategory, BuildingIcon, BuildingPlugin, BuildingType, Facing,
+};
+use crate::fluids::{Fluid, FluidPort, FluidPorts, FluidTank, FluidTanks, PortFlow};
+use crate::items::{Item, ItemStorage};
+use crate::pollution::PollutionEmitter;
+use crate::power::PowerConsumer;
+use crate::stats::{Material, ProductionStats};
 use bevy::prelude::*;
 
+// Crude oil refined per second and what it turns into
+const CRUDE_PER_SECOND: f32 = 10.0;
+const LIGHT_OIL_YIELD: f32 = 0.6;
+const PETROLEUM_GAS_YIELD: f32 = 0.4;
+
+// Solid by-products, one item per this much crude refined
+const CRUDE_PER_PLASTIC: f32 = 20.0;
+const CRUDE_PER_SULFUR: f32 = 50.0;
+
+// kW drawn at full speed, refineries still crawl along without power
+const POWER_DEMAND: f32 = 300.0;
+const UNPOWERED_SPEED: f32 = 0.25;
+
+// Pollution per second while refining
+const POLLUTION: f32 = 4.0;
+
+const CRUDE_TANK: usize = 0;
+const LIGHT_OIL_TANK: usize = 1;
+const PETROLEUM_GAS_TANK: usize = 2;
+
 pub struct OilRefineryPlugin;
 
 impl Plugin for OilRefineryPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<SpawnOilRefineryMsg>()
-            .init_resource::<OilRefineryState>()
-            .init_resource::<OilRefineryAnimTimer>()
+        app.add_plugins(BuildingPlugin::<OilRefinery>::default())
             .add_systems(Startup, setup_oil_refinery)
-            .add_systems(
-                Update,
-                (
-                    start_oil_refinery_preview,
-                    update_oil_refinery_preview,
-                    rotate_oil_refinery_preview,
-                    place_oil_refinery,
-                    animate_oil_refineries,
-                )
-                    .chain(),
-            );
+            .add_systems(Update, refine_crude_oil);
     }
 }
 
-#[derive(Message)]
-pub struct SpawnOilRefineryMsg;
-
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct OilRefineryAsset {
     pub texture: Handle<Image>,
     pub atlas_layout: Handle<TextureAtlasLayout>,
-    pub rotation_indicator: Handle<Image>,
-}
-
-#[derive(Resource, Default)]
-pub struct OilRefineryState {
-    pub placing: bool,
-    pub preview: Option<Entity>,
-    pub rotation: BuildingRotation,
 }
 
-#[derive(Resource)]
-struct OilRefineryAnimTimer {
-    timer: Timer,
-    current_frame: usize,
-}
+#[derive(Component)]
+pub struct OilRefinery;
 
-impl Default for OilRefineryAnimTimer {
-    fn default() -> Self {
-        Self {
-            timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-            current_frame: 0,
+impl BuildingType for OilRefinery {
+    const KIND: BuildingKind = BuildingKind::OilRefinery;
+    const CATEGORY: BuildingCategory = BuildingCategory::Processing;
+    const ICON: BuildingIcon = BuildingIcon::animated("textures/oil_refinery.png", 5);
+    const FACING: Facing = Facing::Indicator(16.0);
+
+    type Assets = OilRefineryAsset;
+
+    fn sprite(assets: &OilRefineryAsset) -> Sprite {
+        Sprite {
+            image: assets.texture.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: assets.atlas_layout.clone(),
+                index: 0,
+            }),
+            ..default()
         }
     }
-}
-
-#[derive(Component)]
-pub struct OilRefinery;
 
-#[derive(Component)]
-pub struct OilRefineryPreview;
+    fn components(_: BuildingRotation) -> impl Bundle {
+        (
+            OilRefinery,
+            BuildingStatus::default(),
+            BuildingAnimation::spin_up(4),
+            ItemStorage::new(20),
+            PowerConsumer::new(POWER_DEMAND, UNPOWERED_SPEED),
+            PollutionEmitter(POLLUTION),
+            RefineryByproducts::default(),
+            FluidTanks(vec![
+                FluidTank::new(200.0).with_filter(Fluid::CrudeOil),
+                FluidTank::new(200.0).with_filter(Fluid::LightOil),
+                FluidTank::new(200.0).with_filter(Fluid::PetroleumGas),
+            ]),
+            // Crude comes in the back, products leave through the front
+            FluidPorts(vec![
+                FluidPort::new(Vec2::new(-1.5, 0.5), PortFlow::Input, CRUDE_TANK),
+                FluidPort::new(Vec2::new(-1.5, -0.5), PortFlow::Input, CRUDE_TANK),
+                FluidPort::new(Vec2::new(1.5, 0.5), PortFlow::Output, LIGHT_OIL_TANK),
+                FluidPort::new(Vec2::new(1.5, -0.5), PortFlow::Output, PETROLEUM_GAS_TANK),
+            ]),
+        )
+    }
+}
 
-#[derive(Component)]
-pub struct RotationIndicator;
+/// Crude refined towards the next solid by-product of each kind.
+#[derive(Component, Default)]
+pub struct RefineryByproducts {
+    pub plastic: f32,
+    pub sulfur: f32,
+}
 
 fn setup_oil_refinery(
     mut commands: Commands,
@@ -70,7 +106,6 @@ fn setup_oil_refinery(
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
 ) {
     let texture = asset_server.load("textures/oil_refinery.png");
-    let rotation_indicator = asset_server.load("textures/rotation_indicator.png");
 
     // Create texture atlas layout for 64x64 spritesheet with 5 frames of 64x64
     let layout = TextureAtlasLayout::from_grid(
@@ -85,196 +120,73 @@ fn setup_oil_refinery(
     commands.insert_resource(OilRefineryAsset {
         texture,
         atlas_layout,
-        rotation_indicator,
     });
 }
 
-fn start_oil_refinery_preview(
-    mut commands: Commands,
-    mut msg_reader: MessageReader<SpawnOilRefineryMsg>,
-    mut state: ResMut<OilRefineryState>,
-    oil_refinery_asset: Res<OilRefineryAsset>,
+fn refine_crude_oil(
+    time: Res<Time>,
+    mut stats: ResMut<ProductionStats>,
+    mut q_refineries: Query<
+        (
+            &mut FluidTanks,
+            &mut ItemStorage,
+            &mut RefineryByproducts,
+            &PowerConsumer,
+            &mut BuildingStatus,
+        ),
+        With<OilRefinery>,
+    >,
 ) {
-    for _ in msg_reader.read() {
-        state.placing = true;
-        state.rotation = BuildingRotation::default(); // Reset thee rotation
-
-        let preview = commands
-            .spawn((
-                OilRefineryPreview,
-                OilRefinery,
-                BuildingRotation::default(),
-                Sprite {
-                    image: oil_refinery_asset.texture.clone(),
-                    texture_atlas: Some(TextureAtlas {
-                        layout: oil_refinery_asset.atlas_layout.clone(),
-                        index: 0,
-                    }),
-                    color: Color::srgba(1.0, 1.0, 1.0, 0.7), // Last value for preview opacity
-                    ..default()
-                },
-                Transform::from_translation(Vec3::new(0.0, 0.0, 10.0)),
-            ))
-            .with_children(|parent| {
-                parent.spawn((
-                    RotationIndicator,
-                    Sprite {
-                        image: oil_refinery_asset.rotation_indicator.clone(),
-                        ..default()
-                    },
-                    Transform::from_xyz(8.0, 0.0, 1.0).with_rotation(Quat::from_rotation_z(0.0)),
-                ));
-            })
-            .id();
+    for (mut tanks, mut storage, mut byproducts, power, mut status) in q_refineries.iter_mut() {
+        let crude = CRUDE_PER_SECOND * power.speed() * time.delta_secs();
 
-        state.preview = Some(preview);
-    }
-}
-
-fn update_oil_refinery_preview(
-    state: Res<OilRefineryState>,
-    q_windows: Query<&Window>,
-    q_camera: Query<(&Camera, &GlobalTransform)>,
-    mut q_preview: Query<&mut Transform, With<OilRefineryPreview>>,
-) {
-    if !state.placing {
-        return;
-    }
+        let tanks = &mut tanks.0;
 
-    let Ok(window) = q_windows.single() else {
-        return;
-    };
-
-    let Some(cursor_pos) = window.cursor_position() else {
-        return;
-    };
-
-    let Ok((camera, camera_transform)) = q_camera.single() else {
-        return;
-    };
-
-    // Convert camera coords -> world coords
-    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else {
-        return;
-    };
-
-    let snapped_pos = snap_to_grid(world_pos, 64.0);
-
-    // Update the preview position
-    if let Some(preview) = state.preview {
-        if let Ok(mut transform) = q_preview.get_mut(preview) {
-            transform.translation = snapped_pos.extend(10.0);
+        // Only run when there is enough input and room for every product
+        if tanks[CRUDE_TANK].amount < crude {
+            *status = BuildingStatus::InputStarved;
+            continue;
         }
-    }
-}
 
-fn rotate_oil_refinery_preview(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mut state: ResMut<OilRefineryState>,
-    q_preview: Query<&Children, With<OilRefineryPreview>>,
-    mut q_preview_rotation: Query<&mut BuildingRotation, With<OilRefineryPreview>>,
-    mut q_indicator: Query<&mut Transform, With<RotationIndicator>>,
-) {
-    if !state.placing {
-        return;
-    }
-
-    if keyboard.just_pressed(KeyCode::KeyR) {
-        // Rotate the state
-        state.rotation.rotate_clockwise();
-
-        // Update BuildingRotation but dont rotate the sprite itself
-        if let Some(preview) = state.preview {
-            if let Ok(mut rotation) = q_preview_rotation.get_mut(preview) {
-                rotation.rotate_clockwise();
-            }
-
-            // Update the rotation indicator's position and rotation
-            if let Ok(children) = q_preview.get(preview) {
-                for child in children.iter() {
-                    if let Ok(mut indicator_transform) = q_indicator.get_mut(child) {
-                        let offset = state.rotation.to_direction() * 16.0;
-                        indicator_transform.translation = offset.extend(1.0);
-
-                        indicator_transform.rotation =
-                            Quat::from_rotation_z(state.rotation.to_radians());
-                    };
-                }
-            }
+        if tanks[LIGHT_OIL_TANK].free_space() < crude * LIGHT_OIL_YIELD
+            || tanks[PETROLEUM_GAS_TANK].free_space() < crude * PETROLEUM_GAS_YIELD
+            || storage.free_space() < 2
+        {
+            *status = BuildingStatus::OutputBlocked;
+            continue;
         }
-    }
-}
 
-fn place_oil_refinery(
-    mut commands: Commands,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    mut state: ResMut<OilRefineryState>,
-    oil_refinery_asset: Res<OilRefineryAsset>,
-    q_preview: Query<(&Transform, &BuildingRotation), With<OilRefineryPreview>>,
-) {
-    if !state.placing {
-        return;
-    }
-
-    if mouse_button.just_pressed(MouseButton::Left) {
-        // Get the preview position
-        if let Some(preview) = state.preview {
-            if let Ok((preview_transform, rotation)) = q_preview.get(preview) {
-                // Now we spawn the oil refinery
-                commands
-                    .spawn((
-                        Building,
-                        OilRefinery,
-                        Pickable::default(),
-                        *rotation,
-                        Sprite {
-                            image: oil_refinery_asset.texture.clone(),
-                            texture_atlas: Some(TextureAtlas {
-                                layout: oil_refinery_asset.atlas_layout.clone(),
-                                index: 0,
-                            }),
-                            ..default()
-                        },
-                        *preview_transform,
-                    ))
-                    .with_children(|parent| {
-                        let offset = rotation.to_direction() * 8.0;
-                        parent.spawn((
-                            RotationIndicator,
-                            Sprite {
-                                image: oil_refinery_asset.rotation_indicator.clone(),
-                                ..default()
-                            },
-                            Transform::from_translation(offset.extend(1.0))
-                                .with_rotation(Quat::from_rotation_z(rotation.to_radians())),
-                        ));
-                    });
-            }
-
-            // And despawn the preview
-            commands.entity(preview).despawn();
+        *status = BuildingStatus::Working;
+
+        tanks[CRUDE_TANK].take(crude);
+        let _ = tanks[LIGHT_OIL_TANK].insert(Fluid::LightOil, crude * LIGHT_OIL_YIELD);
+        let _ = tanks[PETROLEUM_GAS_TANK].insert(Fluid::PetroleumGas, crude * PETROLEUM_GAS_YIELD);
+
+        let kind = BuildingKind::OilRefinery;
+        stats.consumed(Material::Fluid(Fluid::CrudeOil), kind, crude);
+        stats.produced(
+            Material::Fluid(Fluid::LightOil),
+            kind,
+            crude * LIGHT_OIL_YIELD,
+        );
+        stats.produced(
+            Material::Fluid(Fluid::PetroleumGas),
+            kind,
+            crude * PETROLEUM_GAS_YIELD,
+        );
+
+        byproducts.plastic += crude;
+        if byproducts.plastic >= CRUDE_PER_PLASTIC {
+            byproducts.plastic -= CRUDE_PER_PLASTIC;
+            storage.insert(Item::Plastic);
+            stats.produced(Material::Item(Item::Plastic), kind, 1.0);
         }
 
-        // Change state to exit placement mode
-        state.placing = false;
-        state.preview = None;
-    }
-}
-
-fn animate_oil_refineries(
-    time: Res<Time>,
-    mut q_sprite: Query<&mut Sprite, With<OilRefinery>>,
-    mut anim_timer: ResMut<OilRefineryAnimTimer>,
-) {
-    anim_timer.timer.tick(time.delta());
-
-    if anim_timer.timer.just_finished() {
-        anim_timer.current_frame = (anim_timer.current_frame + 1) % 5;
-
-        for mut sprite in q_sprite.iter_mut() {
-            if let Some(ref mut atlas) = sprite.texture_atlas {
-                atlas.index = anim_timer.current_frame;
-            }
+        byproducts.sulfur += crude;
+        if byproducts.sulfur >= CRUDE_PER_SULFUR {
+            byproducts.sulfur -= CRUDE_PER_SULFUR;
+            storage.insert(Item::Sulfur);
+            stats.produced(Material::Item(Item::Sulfur), kind, 1.0);
         }
     }
 }
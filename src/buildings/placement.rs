@@ -0,0 +1,290 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::bindings::{Action, Actions};
+use crate::buildings::helpers::{
+    BUILDING_Z, Building, BuildingKind, BuildingRotation, CancelPlacementMsg, Footprint, TILE_SIZE,
+    cancel_placement_input, snap_to_grid,
+};
+use crate::buildings::registry::{BuildingType, Facing};
+use crate::construction::Unbuilt;
+use crate::game::InWorld;
+use crate::inventory::PlacementCheck;
+
+// Previews are see-through so the ground under them still shows
+const PREVIEW_ALPHA: f32 = 0.7;
+
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<StartPlacementMsg>()
+            .add_message::<CancelPlacementMsg>()
+            .init_resource::<PlacementState>()
+            .configure_sets(
+                Update,
+                (
+                    PlacementSystems::Start,
+                    PlacementSystems::Preview,
+                    PlacementSystems::Place,
+                )
+                    .chain(),
+            )
+            .add_systems(Startup, setup_rotation_indicator)
+            .add_systems(OnExit(InWorld), reset_placement)
+            .add_systems(
+                Update,
+                (
+                    (cancel_placement_input, cancel_placement)
+                        .chain()
+                        .in_set(PlacementSystems::Start),
+                    (move_preview, rotate_preview)
+                        .chain()
+                        .in_set(PlacementSystems::Preview),
+                ),
+            );
+    }
+}
+
+/// Puts a preview of a building under the cursor, the toolbar sends it.
+#[derive(Message)]
+pub struct StartPlacementMsg(pub BuildingKind);
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PlacementSystems {
+    Start,
+    Preview,
+    Place,
+}
+
+/// The building being placed, only one preview follows the cursor at a time.
+#[derive(Resource, Default)]
+pub struct PlacementState {
+    pub kind: Option<BuildingKind>,
+    pub facing: Option<Facing>,
+    pub preview: Option<Entity>,
+    pub rotation: BuildingRotation,
+}
+
+#[derive(Component)]
+pub struct PlacementPreview;
+
+#[derive(Component)]
+pub struct RotationIndicator;
+
+#[derive(Resource, Clone)]
+pub struct RotationIndicatorAsset(pub Handle<Image>);
+
+fn setup_rotation_indicator(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let texture = asset_server.load("textures/rotation_indicator.png");
+
+    commands.insert_resource(RotationIndicatorAsset(texture));
+}
+
+fn indicator_transform(rotation: BuildingRotation, distance: f32) -> Transform {
+    let offset = rotation.to_direction() * distance;
+    Transform::from_translation(offset.extend(1.0))
+        .with_rotation(Quat::from_rotation_z(rotation.to_radians()))
+}
+
+fn rotation_indicator(
+    asset: &RotationIndicatorAsset,
+    rotation: BuildingRotation,
+    distance: f32,
+) -> impl Bundle {
+    (
+        RotationIndicator,
+        Sprite {
+            image: asset.0.clone(),
+            ..default()
+        },
+        indicator_transform(rotation, distance),
+    )
+}
+
+/// Spawns a finished building, placement hands it to construction afterwards.
+pub fn spawn_finished_building<T: BuildingType>(
+    commands: &mut Commands,
+    assets: &T::Assets,
+    indicator: &RotationIndicatorAsset,
+    position: Vec3,
+    rotation: BuildingRotation,
+) -> Entity {
+    let rotation = match T::FACING {
+        Facing::Fixed => BuildingRotation::default(),
+        _ => rotation,
+    };
+
+    let mut transform = Transform::from_translation(position);
+    if T::FACING == Facing::Sprite {
+        transform.rotation = Quat::from_rotation_z(rotation.to_radians());
+    }
+
+    let mut building = commands.spawn((
+        Building,
+        T::KIND,
+        Footprint(T::KIND.footprint()),
+        Pickable::default(),
+        rotation,
+        T::sprite(assets),
+        transform,
+        T::components(rotation),
+    ));
+
+    if let Facing::Indicator(distance) = T::FACING {
+        building.with_child(rotation_indicator(indicator, rotation, distance));
+    }
+
+    building.id()
+}
+
+pub fn cancel_placement(
+    mut commands: Commands,
+    mut msg_reader: MessageReader<CancelPlacementMsg>,
+    mut state: ResMut<PlacementState>,
+) {
+    for _ in msg_reader.read() {
+        if let Some(preview) = state.preview {
+            commands.entity(preview).despawn();
+        }
+        *state = PlacementState::default();
+    }
+}
+
+// The world clear despawns the preview, the next game starts without one
+fn reset_placement(mut state: ResMut<PlacementState>) {
+    *state = PlacementState::default();
+}
+
+pub fn start_preview<T: BuildingType>(
+    mut commands: Commands,
+    mut msg_reader: MessageReader<StartPlacementMsg>,
+    mut state: ResMut<PlacementState>,
+    assets: Res<T::Assets>,
+    indicator: Res<RotationIndicatorAsset>,
+) {
+    for _ in msg_reader.read().filter(|msg| msg.0 == T::KIND) {
+        if let Some(preview) = state.preview {
+            commands.entity(preview).despawn();
+        }
+
+        let rotation = BuildingRotation::default();
+        let mut sprite = T::sprite(&assets);
+        sprite.color = sprite.color.with_alpha(PREVIEW_ALPHA);
+
+        let mut preview = commands.spawn((
+            PlacementPreview,
+            sprite,
+            Transform::from_xyz(0.0, 0.0, BUILDING_Z),
+        ));
+        if let Facing::Indicator(distance) = T::FACING {
+            preview.with_child(rotation_indicator(&indicator, rotation, distance));
+        }
+
+        *state = PlacementState {
+            kind: Some(T::KIND),
+            facing: Some(T::FACING),
+            preview: Some(preview.id()),
+            rotation,
+        };
+    }
+}
+
+fn move_preview(
+    state: Res<PlacementState>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    q_camera: Single<(&Camera, &GlobalTransform)>,
+    mut q_preview: Query<&mut Transform, With<PlacementPreview>>,
+) {
+    let (Some(kind), Some(preview)) = (state.kind, state.preview) else {
+        return;
+    };
+
+    let (camera, camera_transform) = q_camera.into_inner();
+    let Some(world_pos) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    else {
+        return;
+    };
+
+    // Bigger buildings snap to a grid of their own size, so they line up on tile edges
+    let snapped_pos = snap_to_grid(world_pos, TILE_SIZE * kind.footprint() as f32);
+
+    if let Ok(mut transform) = q_preview.get_mut(preview) {
+        transform.translation = snapped_pos.extend(BUILDING_Z);
+    }
+}
+
+fn rotate_preview(
+    actions: Actions,
+    mut state: ResMut<PlacementState>,
+    mut q_preview: Query<(&mut Transform, Option<&Children>), With<PlacementPreview>>,
+    mut q_indicator: Query<&mut Transform, (With<RotationIndicator>, Without<PlacementPreview>)>,
+) {
+    let (Some(facing), Some(preview)) = (state.facing, state.preview) else {
+        return;
+    };
+
+    if facing == Facing::Fixed || !actions.just_pressed(Action::Rotate) {
+        return;
+    }
+
+    state.rotation.rotate_clockwise();
+    let rotation = state.rotation;
+
+    let Ok((mut transform, children)) = q_preview.get_mut(preview) else {
+        return;
+    };
+
+    match facing {
+        Facing::Sprite => transform.rotation = Quat::from_rotation_z(rotation.to_radians()),
+        Facing::Indicator(distance) => {
+            let Some(children) = children else {
+                return;
+            };
+            for child in children.iter() {
+                if let Ok(mut indicator) = q_indicator.get_mut(child) {
+                    *indicator = indicator_transform(rotation, distance);
+                }
+            }
+        }
+        Facing::Fixed => {}
+    }
+}
+
+pub fn place_building<T: BuildingType>(
+    mut commands: Commands,
+    actions: Actions,
+    mut placement: PlacementCheck,
+    mut state: ResMut<PlacementState>,
+    assets: Res<T::Assets>,
+    indicator: Res<RotationIndicatorAsset>,
+    q_preview: Query<&Transform, With<PlacementPreview>>,
+) {
+    if state.kind != Some(T::KIND) || !actions.just_pressed(Action::Place) {
+        return;
+    }
+
+    if let Some(preview) = state.preview {
+        if let Ok(preview_transform) = q_preview.get(preview) {
+            if !placement.allow(T::KIND) {
+                return;
+            }
+
+            let building = spawn_finished_building::<T>(
+                &mut commands,
+                &assets,
+                &indicator,
+                preview_transform.translation,
+                state.rotation,
+            );
+            commands.entity(building).insert(Unbuilt);
+        }
+
+        commands.entity(preview).despawn();
+    }
+
+    // Placing one exits placement mode
+    *state = PlacementState::default();
+}
@@ -0,0 +1,79 @@
+use crate::buildings::helpers::{BuildingKind, BuildingRotation, TILE_SIZE};
+use crate::buildings::placement::{PlacementPreview, PlacementState, PlacementSystems};
+use crate::buildings::registry::{
+    BuildingCategory, BuildingIcon, BuildingPlugin, BuildingType, Facing,
+};
+use crate::power::{POLE_CONNECTION_RADIUS, POLE_SUPPLY_RADIUS, PowerPole};
+use bevy::prelude::*;
+
+pub struct PowerPolePlugin;
+
+impl Plugin for PowerPolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BuildingPlugin::<PowerPole>::default())
+            .add_systems(Startup, setup_power_pole)
+            .add_systems(
+                Update,
+                draw_power_pole_preview_range.after(PlacementSystems::Preview),
+            );
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct PowerPoleAsset {
+    pub texture: Handle<Image>,
+}
+
+impl BuildingType for PowerPole {
+    const KIND: BuildingKind = BuildingKind::PowerPole;
+    const CATEGORY: BuildingCategory = BuildingCategory::Logistics;
+    const ICON: BuildingIcon = BuildingIcon::still("textures/power_pole.png", 1, 0);
+    // Poles look the same from every side
+    const FACING: Facing = Facing::Fixed;
+
+    type Assets = PowerPoleAsset;
+
+    fn sprite(assets: &PowerPoleAsset) -> Sprite {
+        Sprite {
+            image: assets.texture.clone(),
+            ..default()
+        }
+    }
+
+    fn components(_: BuildingRotation) -> impl Bundle {
+        PowerPole
+    }
+}
+
+fn setup_power_pole(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let texture = asset_server.load("textures/power_pole.png");
+
+    commands.insert_resource(PowerPoleAsset { texture });
+}
+
+// Shows what the pole will power and how far it reaches for other poles
+fn draw_power_pole_preview_range(
+    mut gizmos: Gizmos,
+    state: Res<PlacementState>,
+    q_preview: Query<&Transform, With<PlacementPreview>>,
+) {
+    if state.kind != Some(BuildingKind::PowerPole) {
+        return;
+    }
+
+    let Some(Ok(transform)) = state.preview.map(|preview| q_preview.get(preview)) else {
+        return;
+    };
+
+    let center = transform.translation.truncate();
+    gizmos.circle_2d(
+        center,
+        POLE_SUPPLY_RADIUS * TILE_SIZE,
+        Color::srgba_u8(251, 185, 84, 200),
+    );
+    gizmos.circle_2d(
+        center,
+        POLE_CONNECTION_RADIUS * TILE_SIZE,
+        Color::srgba_u8(155, 171, 178, 120),
+    );
+}
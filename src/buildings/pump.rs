@@ -0,0 +1,61 @@
+use crate::buildings::helpers::{BuildingKind, BuildingRotation, BuildingStatus};
+use crate::buildings::registry::{
+    BuildingCategory, BuildingIcon, BuildingPlugin, BuildingType, Facing,
+};
+use crate::fluids::network::FlowDevice;
+use crate::power::PowerConsumer;
+use bevy::prelude::*;
+
+// Pumps move a lot more than a building port can
+const PUMP_RATE: f32 = 200.0;
+
+// kW drawn at full speed, pumps stop entirely without power
+const POWER_DEMAND: f32 = 30.0;
+
+pub struct PumpPlugin;
+
+impl Plugin for PumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BuildingPlugin::<Pump>::default())
+            .add_systems(Startup, setup_pump);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct PumpAsset {
+    pub texture: Handle<Image>,
+}
+
+#[derive(Component)]
+pub struct Pump;
+
+impl BuildingType for Pump {
+    const KIND: BuildingKind = BuildingKind::Pump;
+    const CATEGORY: BuildingCategory = BuildingCategory::Logistics;
+    const ICON: BuildingIcon = BuildingIcon::still("textures/pump.png", 1, 0);
+    const FACING: Facing = Facing::Sprite;
+
+    type Assets = PumpAsset;
+
+    fn sprite(assets: &PumpAsset) -> Sprite {
+        Sprite {
+            image: assets.texture.clone(),
+            ..default()
+        }
+    }
+
+    fn components(_: BuildingRotation) -> impl Bundle {
+        (
+            Pump,
+            BuildingStatus::default(),
+            FlowDevice::Pump { rate: PUMP_RATE },
+            PowerConsumer::new(POWER_DEMAND, 0.0),
+        )
+    }
+}
+
+fn setup_pump(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let texture = asset_server.load("textures/pump.png");
+
+    commands.insert_resource(PumpAsset { texture });
+}
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+use crate::buildings::helpers::{BuildingKind, BuildingRotation};
+use crate::buildings::placement::{
+    PlacementSystems, RotationIndicatorAsset, cancel_placement, place_building,
+    spawn_finished_building, start_preview,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildingCategory {
+    Logistics,
+    Extraction,
+    Storage,
+    Processing,
+}
+
+impl BuildingCategory {
+    pub const ALL: [BuildingCategory; 4] = [
+        BuildingCategory::Logistics,
+        BuildingCategory::Extraction,
+        BuildingCategory::Storage,
+        BuildingCategory::Processing,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildingCategory::Logistics => "Logistics",
+            BuildingCategory::Extraction => "Extraction",
+            BuildingCategory::Storage => "Storage",
+            BuildingCategory::Processing => "Processing",
+        }
+    }
+}
+
+/// Where a building's menu icon lives in its sprite sheet. Sheets are a
+/// single row of equally sized frames.
+#[derive(Clone, Copy, Debug)]
+pub struct BuildingIcon {
+    pub texture: &'static str,
+    pub columns: u32,
+    pub index: u32,
+    // Frames after `index` the icon cycles through, 1 for a still icon
+    pub frames: u32,
+}
+
+impl BuildingIcon {
+    pub const fn still(texture: &'static str, columns: u32, index: u32) -> Self {
+        Self {
+            texture,
+            columns,
+            index,
+            frames: 1,
+        }
+    }
+
+    pub const fn animated(texture: &'static str, frames: u32) -> Self {
+        Self {
+            texture,
+            columns: frames,
+            index: 0,
+            frames,
+        }
+    }
+}
+
+pub struct BuildingInfo {
+    pub kind: BuildingKind,
+    pub category: BuildingCategory,
+    pub icon: BuildingIcon,
+    // Spawns a finished building at a world position, skipping placement and construction
+    pub spawn: fn(&mut World, Vec3, BuildingRotation) -> Entity,
+}
+
+/// Every building type the plugins registered, in registration order.
+#[derive(Resource, Default)]
+pub struct BuildingRegistry {
+    pub buildings: Vec<BuildingInfo>,
+}
+
+impl BuildingRegistry {
+    pub fn get(&self, kind: BuildingKind) -> Option<&BuildingInfo> {
+        self.buildings.iter().find(|info| info.kind == kind)
+    }
+}
+
+pub trait RegisterBuilding {
+    fn register_building(&mut self, info: BuildingInfo) -> &mut Self;
+}
+
+impl RegisterBuilding for App {
+    fn register_building(&mut self, info: BuildingInfo) -> &mut Self {
+        self.init_resource::<BuildingRegistry>();
+        self.world_mut()
+            .resource_mut::<BuildingRegistry>()
+            .buildings
+            .push(info);
+        self
+    }
+}
+
+/// How a building shows which way it faces.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Facing {
+    // The whole sprite turns
+    Sprite,
+    // The sprite stays upright, an arrow this far out from the center points the way
+    Indicator(f32),
+    // Looks the same from every side and always faces the default way
+    Fixed,
+}
+
+/// A kind of building, implemented on its marker component. The shared
+/// placement systems preview, rotate and place it once `BuildingPlugin` adds it.
+pub trait BuildingType: Send + Sync + 'static {
+    const KIND: BuildingKind;
+    const CATEGORY: BuildingCategory;
+    const ICON: BuildingIcon;
+    const FACING: Facing;
+
+    // Loaded by the building's own plugin at startup
+    type Assets: Resource + Clone;
+
+    fn sprite(assets: &Self::Assets) -> Sprite;
+
+    /// What the building needs on top of what every building gets.
+    fn components(rotation: BuildingRotation) -> impl Bundle;
+}
+
+/// Registers `T` and runs the placement systems for it.
+pub struct BuildingPlugin<T: BuildingType>(PhantomData<T>);
+
+impl<T: BuildingType> Default for BuildingPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: BuildingType> Plugin for BuildingPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.register_building(BuildingInfo {
+            kind: T::KIND,
+            category: T::CATEGORY,
+            icon: T::ICON,
+            spawn: spawn_registered::<T>,
+        })
+        .add_systems(
+            Update,
+            (
+                start_preview::<T>
+                    .in_set(PlacementSystems::Start)
+                    .after(cancel_placement),
+                place_building::<T>.in_set(PlacementSystems::Place),
+            ),
+        );
+    }
+}
+
+fn spawn_registered<T: BuildingType>(
+    world: &mut World,
+    position: Vec3,
+    rotation: BuildingRotation,
+) -> Entity {
+    let assets = world.resource::<T::Assets>().clone();
+    let indicator = world.resource::<RotationIndicatorAsset>().clone();
+    spawn_finished_building::<T>(
+        &mut world.commands(),
+        &assets,
+        &indicator,
+        position,
+        rotation,
+    )
+}
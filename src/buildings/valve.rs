@@ -0,0 +1,136 @@
+use crate::buildings::helpers::{BuildingKind, BuildingRotation, BuildingStatus};
+use crate::buildings::registry::{
+    BuildingCategory, BuildingIcon, BuildingPlugin, BuildingType, Facing,
+};
+use crate::fluids::network::FlowDevice;
+use bevy::prelude::*;
+
+const VALVE_RATE: f32 = 100.0;
+
+// Overflow valves open once the network behind them is this full
+const OVERFLOW_THRESHOLD: f32 = 0.8;
+
+pub struct ValvePlugin;
+
+impl Plugin for ValvePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            BuildingPlugin::<CheckValve>::default(),
+            BuildingPlugin::<OverflowValve>::default(),
+        ))
+        .add_systems(Startup, setup_valves);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct ValveAssets {
+    pub texture: Handle<Image>,
+    pub atlas_layout: Handle<TextureAtlasLayout>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ValveKind {
+    Check,
+    Overflow,
+}
+
+impl ValveKind {
+    pub fn atlas_index(&self) -> usize {
+        match self {
+            ValveKind::Check => 0,
+            ValveKind::Overflow => 1,
+        }
+    }
+
+    pub fn flow_device(&self) -> FlowDevice {
+        match self {
+            ValveKind::Check => FlowDevice::CheckValve { rate: VALVE_RATE },
+            ValveKind::Overflow => FlowDevice::OverflowValve {
+                rate: VALVE_RATE,
+                threshold: OVERFLOW_THRESHOLD,
+            },
+        }
+    }
+
+    fn sprite(&self, assets: &ValveAssets) -> Sprite {
+        Sprite {
+            image: assets.texture.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: assets.atlas_layout.clone(),
+                index: self.atlas_index(),
+            }),
+            ..default()
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CheckValve;
+
+impl BuildingType for CheckValve {
+    const KIND: BuildingKind = BuildingKind::CheckValve;
+    const CATEGORY: BuildingCategory = BuildingCategory::Logistics;
+    const ICON: BuildingIcon = BuildingIcon::still("textures/valve.png", 2, 0);
+    const FACING: Facing = Facing::Sprite;
+
+    type Assets = ValveAssets;
+
+    fn sprite(assets: &ValveAssets) -> Sprite {
+        ValveKind::Check.sprite(assets)
+    }
+
+    fn components(_: BuildingRotation) -> impl Bundle {
+        (
+            CheckValve,
+            ValveKind::Check.flow_device(),
+            BuildingStatus::default(),
+        )
+    }
+}
+
+#[derive(Component)]
+pub struct OverflowValve;
+
+impl BuildingType for OverflowValve {
+    const KIND: BuildingKind = BuildingKind::OverflowValve;
+    const CATEGORY: BuildingCategory = BuildingCategory::Logistics;
+    const ICON: BuildingIcon = BuildingIcon::still("textures/valve.png", 2, 1);
+    const FACING: Facing = Facing::Sprite;
+
+    type Assets = ValveAssets;
+
+    fn sprite(assets: &ValveAssets) -> Sprite {
+        ValveKind::Overflow.sprite(assets)
+    }
+
+    fn components(_: BuildingRotation) -> impl Bundle {
+        (
+            OverflowValve,
+            ValveKind::Overflow.flow_device(),
+            BuildingStatus::default(),
+        )
+    }
+}
+
+fn setup_valves(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let texture = asset_server.load("textures/valve.png");
+
+    let layout = TextureAtlasLayout::from_grid(
+        UVec2::new(32, 32),
+        2, // 2 columns
+        1, // 1 row
+        None,
+        None,
+    );
+
+    let atlas_layout = texture_atlases.add(layout);
+
+    commands.insert_resource(ValveAssets {
+        texture,
+        atlas_layout,
+    });
+}
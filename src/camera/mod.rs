@@ -0,0 +1,211 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::bindings::routing::InputRouting;
+use crate::bindings::{Action, Actions};
+use crate::buildings::helpers::TILE_SIZE;
+use crate::game::{GameState, InWorld, MapSettings, Settings};
+
+// Each wheel notch scales the zoom target by this much
+const ZOOM_STEP: f32 = 1.2;
+
+// Touchpads scroll in pixels, this many make up one wheel notch
+const PIXELS_PER_LINE: f32 = 20.0;
+
+// How quickly the zoom catches up with its target, higher is snappier
+const ZOOM_SMOOTHING: f32 = 12.0;
+
+// Pixels from the window border where edge scrolling kicks in
+const EDGE_MARGIN: f32 = 12.0;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_camera)
+            .add_systems(OnEnter(GameState::Loading), reset_camera)
+            .add_systems(
+                Update,
+                (pan_camera, zoom_camera, clamp_camera)
+                    .chain()
+                    .run_if(in_state(InWorld)),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct Speed(f32);
+
+#[derive(Component)]
+pub struct CameraController {
+    speed: Speed,
+
+    max_zoom: f32,
+    min_zoom: f32,
+    // Where the wheel wants the zoom to be, the projection eases towards it
+    target_zoom: f32,
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self {
+            speed: Speed(300.0),
+
+            max_zoom: 4.0,
+            min_zoom: 0.3,
+            target_zoom: 1.0,
+        }
+    }
+}
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((Camera2d, CameraController::default()));
+}
+
+// A new map starts centered and at the default zoom
+fn reset_camera(q_camera: Single<(&mut CameraController, &mut Transform, &mut Projection)>) {
+    let (mut controller, mut transform, mut projection) = q_camera.into_inner();
+
+    transform.translation.x = 0.0;
+    transform.translation.y = 0.0;
+    controller.target_zoom = 1.0;
+    if let Projection::Orthographic(projection2d) = &mut *projection {
+        projection2d.scale = 1.0;
+    }
+}
+
+fn current_zoom(projection: &Projection) -> f32 {
+    match projection {
+        Projection::Orthographic(projection2d) => projection2d.scale,
+        _ => 1.0,
+    }
+}
+
+// Screen space has y pointing down, the world has it pointing up
+fn screen_to_world_offset(offset: Vec2) -> Vec2 {
+    Vec2::new(offset.x, -offset.y)
+}
+
+fn pan_camera(
+    q_camera: Single<(&CameraController, &mut Transform, &Projection)>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    actions: Actions,
+    routing: Res<InputRouting>,
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut last_cursor: Local<Option<Vec2>>,
+) {
+    let (controller, mut transform, projection) = q_camera.into_inner();
+    let zoom = current_zoom(projection);
+
+    // Zoomed out, the same key press covers more of the map
+    let speed = controller.speed.0 * settings.pan_speed * zoom * time.delta_secs();
+    let mut direction = Vec2::ZERO;
+
+    if actions.pressed(Action::PanUp) {
+        direction.y += 1.0;
+    }
+    if actions.pressed(Action::PanDown) {
+        direction.y -= 1.0;
+    }
+    if actions.pressed(Action::PanLeft) {
+        direction.x -= 1.0;
+    }
+    if actions.pressed(Action::PanRight) {
+        direction.x += 1.0;
+    }
+
+    // Windows along the border, like the minimap, don't scroll the map
+    if settings.edge_scroll
+        && window.focused
+        && !routing.pointer_over_ui
+        && let Some(cursor) = window.cursor_position()
+    {
+        let size = window.size();
+        if cursor.x < EDGE_MARGIN {
+            direction.x -= 1.0;
+        }
+        if cursor.x > size.x - EDGE_MARGIN {
+            direction.x += 1.0;
+        }
+        if cursor.y < EDGE_MARGIN {
+            direction.y += 1.0;
+        }
+        if cursor.y > size.y - EDGE_MARGIN {
+            direction.y -= 1.0;
+        }
+    }
+
+    let mut translation = direction.clamp_length_max(1.0) * speed;
+
+    // Dragging keeps the point under the cursor pinned to it, raw mouse motion
+    // would drift from the cursor with pointer acceleration and DPI scaling
+    let cursor = window.cursor_position();
+    if actions.pressed(Action::DragPan)
+        && let (Some(last), Some(cursor)) = (*last_cursor, cursor)
+    {
+        translation -= screen_to_world_offset(cursor - last) * zoom;
+    }
+    *last_cursor = cursor;
+
+    transform.translation += translation.extend(0.0);
+}
+
+fn zoom_camera(
+    q_camera: Single<(&mut CameraController, &mut Transform, &mut Projection)>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut wheel_msg: MessageReader<MouseWheel>,
+    routing: Res<InputRouting>,
+    time: Res<Time<Real>>,
+) {
+    let (mut controller, mut transform, mut projection) = q_camera.into_inner();
+
+    // Scrolling a window's contents leaves the zoom alone
+    for msg in wheel_msg.read() {
+        if routing.pointer_over_ui {
+            continue;
+        }
+        let lines = match msg.unit {
+            MouseScrollUnit::Line => msg.y,
+            MouseScrollUnit::Pixel => msg.y / PIXELS_PER_LINE,
+        };
+        controller.target_zoom *= ZOOM_STEP.powf(lines);
+    }
+    controller.target_zoom = controller
+        .target_zoom
+        .clamp(controller.min_zoom, controller.max_zoom);
+
+    let Projection::Orthographic(projection2d) = &mut *projection else {
+        return;
+    };
+
+    // Ease in log space so zooming in and out feel the same
+    let old_zoom = projection2d.scale;
+    let t = 1.0 - (-ZOOM_SMOOTHING * time.delta_secs()).exp();
+    let new_zoom = (old_zoom.ln() + (controller.target_zoom.ln() - old_zoom.ln()) * t).exp();
+    projection2d.scale = new_zoom;
+
+    // Keep the world point under the cursor in place while the scale changes
+    if let Some(cursor) = window.cursor_position() {
+        let offset = screen_to_world_offset(cursor - window.size() / 2.0);
+        transform.translation += (offset * (old_zoom - new_zoom)).extend(0.0);
+    }
+}
+
+// The view stays on the map, and centered on it once the map fits on screen
+fn clamp_camera(
+    q_camera: Single<(&mut Transform, &Projection), With<CameraController>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    map_settings: Res<MapSettings>,
+) {
+    let (mut transform, projection) = q_camera.into_inner();
+
+    // The tilemap is anchored on its center at the origin
+    let half_map = map_settings.size.tiles() as f32 * TILE_SIZE / 2.0;
+    let half_view = window.size() / 2.0 * current_zoom(projection);
+    let limit = (Vec2::splat(half_map) - half_view).max(Vec2::ZERO);
+
+    transform.translation.x = transform.translation.x.clamp(-limit.x, limit.x);
+    transform.translation.y = transform.translation.y.clamp(-limit.y, limit.y);
+}
@@ -0,0 +1,273 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::ecs::query::Allow;
+use bevy::prelude::*;
+use std::str::FromStr;
+
+use crate::buildings::helpers::{Building, BuildingKind, BuildingRotation};
+use crate::console::{Console, MAX_TICKS};
+use crate::construction::{ConstructionQueue, ConstructionSite};
+use crate::fluids::{FluidTank, FluidTanks};
+use crate::game::{GameState, MapSettings};
+use crate::inventory::Inventory;
+use crate::items::ItemStorage;
+use crate::research::Research;
+use crate::save::{list_saves, load_game, save_game, spawn_building};
+use crate::stats::Material;
+
+struct CommandInfo {
+    name: &'static str,
+    usage: &'static str,
+    help: &'static str,
+}
+
+const COMMANDS: [CommandInfo; 11] = [
+    CommandInfo {
+        name: "help",
+        usage: "help",
+        help: "List every command",
+    },
+    CommandInfo {
+        name: "spawn",
+        usage: "spawn <building> <x> <y> [rotation]",
+        help: "Spawn a finished building on a tile",
+    },
+    CommandInfo {
+        name: "fill",
+        usage: "fill <entity> <material> <amount>",
+        help: "Put fluid in a building's tanks or items in its storage",
+    },
+    CommandInfo {
+        name: "give",
+        usage: "give <material> <amount>",
+        help: "Add to the inventory",
+    },
+    CommandInfo {
+        name: "unlock",
+        usage: "unlock",
+        help: "Complete all research",
+    },
+    CommandInfo {
+        name: "tick",
+        usage: "tick <count>",
+        help: "Run the simulation for a number of frames",
+    },
+    CommandInfo {
+        name: "seed",
+        usage: "seed <seed>",
+        help: "Start over on a new map with this seed",
+    },
+    CommandInfo {
+        name: "clear",
+        usage: "clear",
+        help: "Remove every building and construction site",
+    },
+    CommandInfo {
+        name: "save",
+        usage: "save <name>",
+        help: "Save the game",
+    },
+    CommandInfo {
+        name: "load",
+        usage: "load <name>",
+        help: "Load a saved game",
+    },
+    CommandInfo {
+        name: "history",
+        usage: "history",
+        help: "List the commands run so far",
+    },
+];
+
+/// Runs one line of input, returning what to print.
+pub fn run(world: &mut World, line: &str) -> Result<String, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["help"] => Ok(COMMANDS
+            .iter()
+            .map(|command| format!("{:<40}{}", command.usage, command.help))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        ["spawn", kind, x, y] => spawn(world, kind, x, y, "east"),
+        ["spawn", kind, x, y, rotation] => spawn(world, kind, x, y, rotation),
+        ["fill", entity, material, amount] => fill(world, entity, material, amount),
+        ["give", material, amount] => {
+            let material = parse_material(material)?;
+            let amount: f32 = parse(amount, "amount")?;
+            world.resource_mut::<Inventory>().add(material, amount);
+            Ok(format!("Added {amount} {material} to the inventory"))
+        }
+        ["unlock"] => {
+            world.resource_mut::<Research>().unlock_all();
+            Ok("All research completed".to_string())
+        }
+        ["tick", count] => {
+            let count = parse::<u32>(count, "tick count")?.min(MAX_TICKS);
+            world.resource_mut::<Console>().ticks += count;
+            Ok(format!("Running {count} ticks"))
+        }
+        ["seed", seed] => {
+            let seed = parse(seed, "seed")?;
+            world.resource_mut::<MapSettings>().seed = seed;
+            world
+                .resource_mut::<NextState<GameState>>()
+                .set(GameState::Loading);
+            Ok(format!("Generating a new map with seed {seed}"))
+        }
+        ["clear"] => Ok(format!("Removed {} buildings", clear(world))),
+        ["save", name] => {
+            let count = save_game(world, name)?;
+            Ok(format!("Saved {count} buildings to '{name}'"))
+        }
+        ["load", name] => {
+            load_game(world, name)?;
+            Ok(format!("Loading '{name}'"))
+        }
+        ["history"] => Ok(world.resource::<Console>().history.join("\n")),
+        [name, ..] => match COMMANDS.iter().find(|command| command.name == *name) {
+            Some(command) => Err(format!("usage: {}", command.usage)),
+            None => Err(format!("unknown command '{name}', try 'help'")),
+        },
+        [] => Ok(String::new()),
+    }
+}
+
+fn parse<T: FromStr>(word: &str, what: &str) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("'{word}' is not a valid {what}"))
+}
+
+fn parse_material(word: &str) -> Result<Material, String> {
+    Material::from_id(word).ok_or_else(|| format!("unknown material '{word}'"))
+}
+
+// Entities print as `12v0`. The generation is optional, but without it a
+// reused index can point at a different building
+fn find_building(world: &mut World, word: &str) -> Result<Entity, String> {
+    let (index, generation) = match word.split_once('v') {
+        Some((index, generation)) => (index, Some(generation)),
+        None => (word, None),
+    };
+    let index: u32 = parse(index, "entity index")?;
+
+    let mut q_buildings = world.query_filtered::<Entity, With<Building>>();
+    q_buildings
+        .iter(world)
+        .find(|entity| {
+            entity.index() == index
+                && generation.is_none_or(|generation| entity.generation().to_string() == generation)
+        })
+        .ok_or_else(|| format!("no building with id '{word}'"))
+}
+
+fn spawn(
+    world: &mut World,
+    kind: &str,
+    x: &str,
+    y: &str,
+    rotation: &str,
+) -> Result<String, String> {
+    let kind = BuildingKind::from_id(kind).ok_or_else(|| format!("unknown building '{kind}'"))?;
+    let tile = (parse(x, "tile x")?, parse(y, "tile y")?);
+    let rotation = BuildingRotation::from_id(rotation)
+        .ok_or_else(|| format!("unknown rotation '{rotation}'"))?;
+
+    let entity = spawn_building(world, kind, tile, rotation)
+        .ok_or_else(|| format!("{} is not registered", kind.name()))?;
+    Ok(format!(
+        "Spawned {} {entity} at ({}, {})",
+        kind.name(),
+        tile.0,
+        tile.1
+    ))
+}
+
+fn fill(world: &mut World, entity: &str, material: &str, amount: &str) -> Result<String, String> {
+    let entity = find_building(world, entity)?;
+    let material = parse_material(material)?;
+    let amount: f32 = parse(amount, "amount")?;
+
+    let mut entity_mut = world.entity_mut(entity);
+    let added = match material {
+        Material::Fluid(fluid) => {
+            let mut left = amount;
+
+            // Pipes hold a single tank of their own
+            if let Some(mut tank) = entity_mut.get_mut::<FluidTank>() {
+                left -= tank.insert(fluid, left).unwrap_or(0.0);
+            } else if let Some(mut tanks) = entity_mut.get_mut::<FluidTanks>() {
+                for tank in tanks.0.iter_mut() {
+                    left -= tank.insert(fluid, left).unwrap_or(0.0);
+                }
+            } else {
+                return Err(format!("{entity} has no fluid tanks"));
+            }
+            amount - left
+        }
+        Material::Item(item) => {
+            let Some(mut storage) = entity_mut.get_mut::<ItemStorage>() else {
+                return Err(format!("{entity} has no item storage"));
+            };
+
+            let count = amount.max(0.0) as u32;
+            (0..count).take_while(|_| storage.insert(item)).count() as f32
+        }
+    };
+
+    Ok(format!("Added {added} {material} to {entity}"))
+}
+
+// Buildings that are unbuilt or being torn down are disabled and need asking for
+fn clear(world: &mut World) -> usize {
+    let mut q_buildings = world.query_filtered::<(Entity, Has<Building>), (
+        Or<(With<Building>, With<ConstructionSite>)>,
+        Allow<Disabled>,
+    )>();
+    let entities: Vec<(Entity, bool)> = q_buildings.iter(world).collect();
+
+    for (entity, _) in &entities {
+        world.despawn(*entity);
+    }
+    world.resource_mut::<ConstructionQueue>().0.clear();
+
+    entities.iter().filter(|(_, building)| *building).count()
+}
+
+/// What the word being typed could complete to.
+pub fn candidates(input: &str) -> Vec<String> {
+    let mut words: Vec<&str> = input.split_whitespace().collect();
+    // A trailing space starts a new, empty word
+    if input.is_empty() || input.ends_with(char::is_whitespace) {
+        words.push("");
+    }
+
+    let Some((typed, previous)) = words.split_last() else {
+        return Vec::new();
+    };
+
+    let options: Vec<String> = match previous {
+        [] => COMMANDS
+            .iter()
+            .map(|command| command.name.to_string())
+            .collect(),
+        ["spawn"] => BuildingKind::ALL
+            .iter()
+            .map(|kind| kind.id().to_string())
+            .collect(),
+        ["spawn", _, _, _] => BuildingRotation::ALL
+            .iter()
+            .map(|rotation| rotation.id().to_string())
+            .collect(),
+        ["fill", _] | ["give"] => Material::ALL
+            .iter()
+            .map(|material| material.id().to_string())
+            .collect(),
+        ["save"] | ["load"] => list_saves(),
+        _ => Vec::new(),
+    };
+
+    options
+        .into_iter()
+        .filter(|option| option.starts_with(*typed))
+        .collect()
+}
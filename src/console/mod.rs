@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::bindings::{Action, Actions};
+use crate::game::InWorld;
+
+pub mod commands;
+
+// Anything longer and the frame it runs in stalls for too long
+pub const MAX_TICKS: u32 = 10_000;
+
+// Simulated time per `tick`, one frame at 60 fps
+const TICK: Duration = Duration::from_micros(16_667);
+
+const MAX_OUTPUT_LINES: usize = 200;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        // Developer tools only, release builds don't get the console
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        app.init_resource::<Console>()
+            .add_systems(Update, toggle_console.run_if(in_state(InWorld)))
+            // Commands run after everything else so `tick` can step the Update schedule
+            .add_systems(Last, run_console_commands.run_if(in_state(InWorld)));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    Input,
+    Output,
+    Error,
+}
+
+/// The developer console, toggled with the backtick key.
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub output: Vec<(LineKind, String)>,
+    // Submitted commands, oldest first
+    pub history: Vec<String>,
+    // Which history entry the arrow keys are on, None while typing a new command
+    pub history_index: Option<usize>,
+    // Lines submitted from the UI, run at the end of the frame
+    pub pending: Vec<String>,
+    ticks: u32,
+}
+
+impl Console {
+    pub fn print(&mut self, kind: LineKind, text: impl Into<String>) {
+        self.output.push((kind, text.into()));
+
+        let excess = self.output.len().saturating_sub(MAX_OUTPUT_LINES);
+        self.output.drain(..excess);
+    }
+
+    pub fn submit(&mut self) {
+        let line = self.input.trim().to_string();
+        self.input.clear();
+        self.history_index = None;
+
+        if line.is_empty() {
+            return;
+        }
+
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        self.pending.push(line);
+    }
+
+    /// Completes the word being typed as far as it is unambiguous and lists
+    /// the options when there are several.
+    pub fn autocomplete(&mut self) {
+        let candidates = commands::candidates(&self.input);
+        let Some(first) = candidates.first() else {
+            return;
+        };
+
+        let mut prefix = first.clone();
+        for candidate in &candidates[1..] {
+            while !candidate.starts_with(&prefix) {
+                prefix.pop();
+            }
+        }
+
+        let start = self
+            .input
+            .trim_end_matches(|c: char| !c.is_whitespace())
+            .len();
+        self.input.truncate(start);
+        self.input.push_str(&prefix);
+
+        if candidates.len() == 1 {
+            self.input.push(' ');
+        } else {
+            let options = candidates.join("  ");
+            self.print(LineKind::Output, options);
+        }
+    }
+
+    pub fn history_previous(&mut self) {
+        let index = match self.history_index {
+            Some(index) => index.saturating_sub(1),
+            None if !self.history.is_empty() => self.history.len() - 1,
+            None => return,
+        };
+        self.history_index = Some(index);
+        self.input = self.history[index].clone();
+    }
+
+    pub fn history_next(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.input = self.history[index + 1].clone();
+        } else {
+            self.history_index = None;
+            self.input.clear();
+        }
+    }
+}
+
+fn toggle_console(actions: Actions, mut console: ResMut<Console>) {
+    if actions.just_pressed(Action::ToggleConsole) {
+        console.open = !console.open;
+    }
+}
+
+fn run_console_commands(world: &mut World) {
+    let lines = std::mem::take(&mut world.resource_mut::<Console>().pending);
+
+    for line in lines {
+        let result = commands::run(world, &line);
+
+        let mut console = world.resource_mut::<Console>();
+        console.print(LineKind::Input, format!("> {line}"));
+        match result {
+            Ok(text) if text.is_empty() => {}
+            Ok(text) => console.print(LineKind::Output, text),
+            Err(text) => console.print(LineKind::Error, text),
+        }
+    }
+
+    // Step the simulation on virtual time, the same clock it normally runs on
+    let ticks = std::mem::take(&mut world.resource_mut::<Console>().ticks);
+    for _ in 0..ticks {
+        let mut virtual_time = world.resource_mut::<Time<Virtual>>();
+        virtual_time.advance_by(TICK);
+        let generic = virtual_time.as_generic();
+        *world.resource_mut::<Time>() = generic;
+
+        world.run_schedule(Update);
+    }
+}
@@ -0,0 +1,257 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::prelude::*;
+
+use crate::buildings::helpers::{BuildingKind, DeleteMode, Footprint, TILE_SIZE};
+use crate::inventory::Stockpile;
+
+// How many construction sites are worked on at once, the rest wait their turn
+const CONSTRUCTION_CREWS: usize = 2;
+
+const GHOST_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.7);
+const WAITING_COLOR: Color = Color::srgba(1.0, 0.55, 0.55, 0.5);
+
+pub struct ConstructionPlugin;
+
+impl Plugin for ConstructionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConstructionQueue>().add_systems(
+            Update,
+            (
+                begin_construction,
+                cancel_clicked_construction,
+                run_construction,
+                draw_construction_progress,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Placed but not built yet. The building stays disabled, so it takes no part
+/// in the simulation, while a ghost stands in for it.
+#[derive(Component, Default)]
+#[require(Disabled)]
+pub struct Unbuilt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstructionJob {
+    Build,
+    Deconstruct,
+}
+
+/// A ghost of a building that is being built or torn down.
+#[derive(Component)]
+pub struct ConstructionSite {
+    pub building: Entity,
+    pub kind: BuildingKind,
+    pub job: ConstructionJob,
+    // Builds are paid for and given a crew once their turn comes
+    pub started: bool,
+    pub timer: Timer,
+    size: i32,
+}
+
+impl ConstructionSite {
+    pub fn progress(&self) -> f32 {
+        if self.started {
+            self.timer.fraction()
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Construction sites in the order they were placed.
+#[derive(Resource, Default)]
+pub struct ConstructionQueue(pub Vec<Entity>);
+
+pub fn spawn_construction_site(
+    commands: &mut Commands,
+    building: Entity,
+    kind: BuildingKind,
+    job: ConstructionJob,
+    sprite: &Sprite,
+    transform: Transform,
+    size: i32,
+) -> Entity {
+    // Tearing down is quicker than putting up and needs no crew
+    let (seconds, started) = match job {
+        ConstructionJob::Build => (kind.build_time(), false),
+        ConstructionJob::Deconstruct => (kind.build_time() / 2.0, true),
+    };
+
+    commands
+        .spawn((
+            ConstructionSite {
+                building,
+                kind,
+                job,
+                started,
+                timer: Timer::from_seconds(seconds, TimerMode::Once),
+                size,
+            },
+            Sprite {
+                color: GHOST_COLOR,
+                ..sprite.clone()
+            },
+            transform,
+            Pickable::default(),
+        ))
+        .id()
+}
+
+// Brings a disabled building back and makes sure its transform and visibility
+// get propagated again, they were skipped while it was disabled
+fn enable_building(commands: &mut Commands, building: Entity, transform: Transform) {
+    commands
+        .entity(building)
+        .remove::<(Unbuilt, Disabled)>()
+        .insert((transform, Visibility::Inherited));
+}
+
+fn begin_construction(
+    mut commands: Commands,
+    mut queue: ResMut<ConstructionQueue>,
+    q_unbuilt: Query<
+        (
+            Entity,
+            &BuildingKind,
+            &Sprite,
+            &Transform,
+            Option<&Footprint>,
+        ),
+        (Added<Unbuilt>, With<Disabled>),
+    >,
+) {
+    for (building, kind, sprite, transform, footprint) in q_unbuilt.iter() {
+        let ghost = spawn_construction_site(
+            &mut commands,
+            building,
+            *kind,
+            ConstructionJob::Build,
+            sprite,
+            *transform,
+            footprint.map(|f| f.0).unwrap_or(1),
+        );
+        queue.0.push(ghost);
+    }
+}
+
+fn cancel_clicked_construction(
+    mut commands: Commands,
+    mut msg_reader: MessageReader<Pointer<Click>>,
+    delete_mode: Res<DeleteMode>,
+    mut queue: ResMut<ConstructionQueue>,
+    mut stockpile: Stockpile,
+    q_sites: Query<(&ConstructionSite, &Transform)>,
+) {
+    if !delete_mode.active {
+        return;
+    }
+
+    for msg in msg_reader.read() {
+        let Ok((site, transform)) = q_sites.get(msg.entity) else {
+            continue;
+        };
+
+        match site.job {
+            // Only started builds have been paid for
+            ConstructionJob::Build => {
+                if site.started {
+                    for (material, amount) in site.kind.cost().iter() {
+                        stockpile.inventory.add(*material, *amount);
+                    }
+                }
+                commands.entity(site.building).despawn();
+            }
+            // Changed our mind, put it back as it was
+            ConstructionJob::Deconstruct => {
+                enable_building(&mut commands, site.building, *transform);
+            }
+        }
+
+        queue.0.retain(|ghost| *ghost != msg.entity);
+        commands.entity(msg.entity).despawn();
+    }
+}
+
+fn run_construction(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut queue: ResMut<ConstructionQueue>,
+    mut stockpile: Stockpile,
+    mut q_sites: Query<(&mut ConstructionSite, &mut Sprite, &Transform)>,
+) {
+    let mut busy = q_sites
+        .iter()
+        .filter(|(site, ..)| site.started && site.job == ConstructionJob::Build)
+        .count();
+
+    let mut finished = Vec::new();
+
+    for ghost in queue.0.iter() {
+        let Ok((mut site, mut sprite, transform)) = q_sites.get_mut(*ghost) else {
+            continue;
+        };
+
+        // Hand out free crews in placement order, skipping sites that can't be
+        // paid for yet so one planned building doesn't hold up the rest
+        if !site.started && busy < CONSTRUCTION_CREWS {
+            if stockpile.pay(site.kind.cost()).is_ok() {
+                site.started = true;
+                busy += 1;
+                sprite.color = GHOST_COLOR;
+            } else {
+                sprite.color = WAITING_COLOR;
+            }
+        }
+
+        if !site.started {
+            continue;
+        }
+
+        site.timer.tick(time.delta());
+        if !site.timer.just_finished() {
+            continue;
+        }
+
+        match site.job {
+            ConstructionJob::Build => {
+                enable_building(&mut commands, site.building, *transform);
+            }
+            // Whatever the building held is lost, the materials come back
+            ConstructionJob::Deconstruct => {
+                for (material, amount) in site.kind.cost().iter() {
+                    stockpile.inventory.add(*material, *amount);
+                }
+                commands.entity(site.building).despawn();
+            }
+        }
+
+        commands.entity(*ghost).despawn();
+        finished.push(*ghost);
+    }
+
+    queue.0.retain(|ghost| !finished.contains(ghost));
+}
+
+fn draw_construction_progress(mut gizmos: Gizmos, q_sites: Query<(&ConstructionSite, &Transform)>) {
+    for (site, transform) in q_sites.iter() {
+        if !site.started {
+            continue;
+        }
+
+        let half = site.size as f32 * TILE_SIZE / 2.0;
+        let left = transform.translation.truncate() + Vec2::new(-half + 4.0, -half + 4.0);
+        let width = half * 2.0 - 8.0;
+
+        // Deconstruction counts down
+        let (fraction, color) = match site.job {
+            ConstructionJob::Build => (site.progress(), Color::srgb_u8(251, 185, 84)),
+            ConstructionJob::Deconstruct => (1.0 - site.progress(), Color::srgb_u8(230, 70, 60)),
+        };
+
+        gizmos.line_2d(left, left + Vec2::X * width, Color::srgb_u8(46, 34, 47));
+        gizmos.line_2d(left, left + Vec2::X * width * fraction, color);
+    }
+}
@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use std::fmt;
+
+use crate::fluids::network::{
+    FlushPipeNetworkMsg, PipeNetworks, distribute_network_fluid, flush_pipe_networks,
+    rebuild_pipe_networks, tint_pipes_by_fluid, transfer_device_fluids, transfer_port_fluids,
+};
+
+pub mod network;
+
+pub struct FluidsPlugin;
+
+impl Plugin for FluidsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<FlushPipeNetworkMsg>()
+            .init_resource::<PipeNetworks>()
+            .add_systems(
+                Update,
+                (
+                    flush_pipe_networks,
+                    rebuild_pipe_networks,
+                    transfer_port_fluids,
+                    transfer_device_fluids,
+                    distribute_network_fluid,
+                    tint_pipes_by_fluid,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fluid {
+    CrudeOil,
+    LightOil,
+    PetroleumGas,
+}
+
+impl Fluid {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fluid::CrudeOil => "Crude Oil",
+            Fluid::LightOil => "Light Oil",
+            Fluid::PetroleumGas => "Petroleum Gas",
+        }
+    }
+
+    // Sprite colors multiply the texture, and the pipe texture is quite dark,
+    // so these go above 1.0 to actually show up
+    pub fn tint(&self) -> Color {
+        match self {
+            Fluid::CrudeOil => Color::linear_rgb(1.6, 1.2, 0.9),
+            Fluid::LightOil => Color::linear_rgb(3.0, 2.4, 0.6),
+            Fluid::PetroleumGas => Color::linear_rgb(2.4, 1.2, 2.8),
+        }
+    }
+
+    // Plain color for egui labels
+    pub fn ui_color(&self) -> [u8; 3] {
+        match self {
+            Fluid::CrudeOil => [110, 90, 80],
+            Fluid::LightOil => [230, 200, 80],
+            Fluid::PetroleumGas => [200, 120, 220],
+        }
+    }
+}
+
+impl fmt::Display for Fluid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Raised when two different fluids would end up in the same tank or network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FluidMixError {
+    pub existing: Fluid,
+    pub incoming: Fluid,
+}
+
+impl fmt::Display for FluidMixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot mix {} into {}",
+            self.incoming.name(),
+            self.existing.name()
+        )
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FluidTank {
+    pub fluid: Option<Fluid>,
+    pub amount: f32,
+    pub capacity: f32,
+    // Only this fluid may ever enter the tank (e.g. a refinery's crude input)
+    pub filter: Option<Fluid>,
+}
+
+impl FluidTank {
+    pub fn new(capacity: f32) -> Self {
+        Self {
+            fluid: None,
+            amount: 0.0,
+            capacity,
+            filter: None,
+        }
+    }
+
+    pub fn with_filter(mut self, fluid: Fluid) -> Self {
+        self.filter = Some(fluid);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amount <= f32::EPSILON
+    }
+
+    pub fn free_space(&self) -> f32 {
+        (self.capacity - self.amount).max(0.0)
+    }
+
+    pub fn fill_ratio(&self) -> f32 {
+        if self.capacity <= 0.0 {
+            0.0
+        } else {
+            self.amount / self.capacity
+        }
+    }
+
+    /// Checks whether `fluid` may enter this tank without mixing.
+    pub fn check(&self, fluid: Fluid) -> Result<(), FluidMixError> {
+        if let Some(filter) = self.filter
+            && filter != fluid
+        {
+            return Err(FluidMixError {
+                existing: filter,
+                incoming: fluid,
+            });
+        }
+
+        match self.fluid {
+            Some(existing) if existing != fluid && !self.is_empty() => Err(FluidMixError {
+                existing,
+                incoming: fluid,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Inserts up to `amount` of `fluid`, returning how much was accepted.
+    pub fn insert(&mut self, fluid: Fluid, amount: f32) -> Result<f32, FluidMixError> {
+        self.check(fluid)?;
+
+        let accepted = amount.min(self.free_space());
+        if accepted > 0.0 {
+            self.fluid = Some(fluid);
+            self.amount += accepted;
+        }
+        Ok(accepted)
+    }
+
+    /// Removes up to `amount`, returning how much was taken.
+    pub fn take(&mut self, amount: f32) -> f32 {
+        let taken = amount.min(self.amount);
+        self.amount -= taken;
+        if self.is_empty() {
+            self.clear();
+        }
+        taken
+    }
+
+    pub fn clear(&mut self) {
+        self.fluid = None;
+        self.amount = 0.0;
+    }
+}
+
+/// The internal tanks of a building, indexed by its `FluidPorts`.
+#[derive(Component, Clone, Debug, Default)]
+pub struct FluidTanks(pub Vec<FluidTank>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortFlow {
+    Input,
+    Output,
+    Both,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FluidPort {
+    // Offset from the building center in tiles, as if the building faced East
+    pub offset: Vec2,
+    pub flow: PortFlow,
+    pub tank: usize,
+}
+
+impl FluidPort {
+    pub fn new(offset: Vec2, flow: PortFlow, tank: usize) -> Self {
+        Self { offset, flow, tank }
+    }
+}
+
+#[derive(Component, Clone, Debug, Default)]
+pub struct FluidPorts(pub Vec<FluidPort>);
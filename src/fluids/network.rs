@@ -0,0 +1,442 @@
+use crate::buildings::helpers::{BuildingRotation, BuildingStatus, TILE_SIZE, world_to_grid};
+use crate::buildings::pipe::Pipe;
+use crate::fluids::{Fluid, FluidMixError, FluidPorts, FluidTank, FluidTanks, PortFlow};
+use crate::power::PowerConsumer;
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+pub const PIPE_CAPACITY: f32 = 100.0;
+
+// How much fluid a single building port can move per second
+pub const PORT_FLOW_RATE: f32 = 60.0;
+
+// Flow per second at which a pipe network counts as saturated
+pub const PIPE_THROUGHPUT: f32 = 120.0;
+
+/// Pipe network buildings that move fluid from the network behind them to the
+/// network in front of them. They are not pipes, so they split networks.
+#[derive(Component, Clone, Copy, Debug)]
+pub enum FlowDevice {
+    // Moves fluid forward regardless of how full either side is
+    Pump { rate: f32 },
+    // Lets fluid through only while the back is fuller than the front
+    CheckValve { rate: f32 },
+    // Lets fluid through only while the back is filled above `threshold`
+    OverflowValve { rate: f32, threshold: f32 },
+}
+
+#[derive(Message)]
+pub struct FlushPipeNetworkMsg {
+    pub tile: (i32, i32),
+}
+
+#[derive(Debug, Default)]
+pub struct PipeNetwork {
+    pub pipes: Vec<Entity>,
+    pub tiles: Vec<(i32, i32)>,
+    pub fluid: Option<Fluid>,
+    pub amount: f32,
+    pub capacity: f32,
+    // The pipes already hold more than one fluid, nothing moves until it is flushed
+    pub mixed: bool,
+    pub error: Option<FluidMixError>,
+    // Fluid entering through ports and devices, per second
+    pub flow: f32,
+}
+
+impl PipeNetwork {
+    pub fn free_space(&self) -> f32 {
+        (self.capacity - self.amount).max(0.0)
+    }
+
+    pub fn fill_ratio(&self) -> f32 {
+        if self.capacity <= 0.0 {
+            0.0
+        } else {
+            self.amount / self.capacity
+        }
+    }
+
+    // How close the network is to carrying as much as it can
+    pub fn utilization(&self) -> f32 {
+        (self.flow / PIPE_THROUGHPUT).min(1.0)
+    }
+}
+
+/// Connected pipe segments, rebuilt every frame from the placed pipes.
+#[derive(Resource, Default)]
+pub struct PipeNetworks {
+    pub networks: Vec<PipeNetwork>,
+    pub by_tile: HashMap<(i32, i32), usize>,
+}
+
+impl PipeNetworks {
+    pub fn index_at(&self, tile: (i32, i32)) -> Option<usize> {
+        self.by_tile.get(&tile).copied()
+    }
+
+    pub fn at(&self, tile: (i32, i32)) -> Option<&PipeNetwork> {
+        self.index_at(tile).map(|index| &self.networks[index])
+    }
+}
+
+// Same rule `update_pipe_connections` uses to pick sprites: neighbors connect
+// along an axis if either of them points along it
+pub fn pipes_connect(
+    a: (i32, i32),
+    a_rotation: BuildingRotation,
+    b: (i32, i32),
+    b_rotation: BuildingRotation,
+) -> bool {
+    if a.1 == b.1 && (a.0 - b.0).abs() == 1 {
+        a_rotation.is_horizontal() || b_rotation.is_horizontal()
+    } else if a.0 == b.0 && (a.1 - b.1).abs() == 1 {
+        !a_rotation.is_horizontal() || !b_rotation.is_horizontal()
+    } else {
+        false
+    }
+}
+
+pub fn port_tile(center: Vec3, rotation: BuildingRotation, offset: Vec2) -> (i32, i32) {
+    let world_pos = center.truncate() + rotation.rotate_vec(offset) * TILE_SIZE;
+    world_to_grid(world_pos.extend(0.0))
+}
+
+pub fn rebuild_pipe_networks(
+    mut networks: ResMut<PipeNetworks>,
+    q_pipes: Query<(Entity, &Transform, &BuildingRotation, &FluidTank), With<Pipe>>,
+) {
+    let mut pipes: HashMap<(i32, i32), (Entity, BuildingRotation, FluidTank)> = HashMap::new();
+    for (entity, transform, rotation, tank) in q_pipes.iter() {
+        pipes.insert(
+            world_to_grid(transform.translation),
+            (entity, *rotation, *tank),
+        );
+    }
+
+    // Sort so network indices stay stable between frames
+    let mut tiles: Vec<(i32, i32)> = pipes.keys().copied().collect();
+    tiles.sort();
+
+    networks.networks.clear();
+    networks.by_tile.clear();
+
+    for start in tiles {
+        if networks.by_tile.contains_key(&start) {
+            continue;
+        }
+
+        let index = networks.networks.len();
+        let mut network = PipeNetwork::default();
+        let mut queue = VecDeque::from([start]);
+        networks.by_tile.insert(start, index);
+
+        while let Some(tile) = queue.pop_front() {
+            let (entity, rotation, tank) = pipes[&tile];
+
+            network.pipes.push(entity);
+            network.tiles.push(tile);
+            network.capacity += tank.capacity;
+
+            if let Some(fluid) = tank.fluid
+                && !tank.is_empty()
+            {
+                match network.fluid {
+                    Some(existing) if existing != fluid => {
+                        network.mixed = true;
+                        network.error = Some(FluidMixError {
+                            existing,
+                            incoming: fluid,
+                        });
+                    }
+                    _ => network.fluid = Some(fluid),
+                }
+                network.amount += tank.amount;
+            }
+
+            let (x, y) = tile;
+            for neighbor in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                let Some((_, neighbor_rotation, _)) = pipes.get(&neighbor) else {
+                    continue;
+                };
+
+                if networks.by_tile.contains_key(&neighbor)
+                    || !pipes_connect(tile, rotation, neighbor, *neighbor_rotation)
+                {
+                    continue;
+                }
+
+                networks.by_tile.insert(neighbor, index);
+                queue.push_back(neighbor);
+            }
+        }
+
+        networks.networks.push(network);
+    }
+}
+
+pub fn transfer_port_fluids(
+    time: Res<Time>,
+    mut networks: ResMut<PipeNetworks>,
+    mut q_buildings: Query<(&Transform, &BuildingRotation, &FluidPorts, &mut FluidTanks)>,
+) {
+    let budget = PORT_FLOW_RATE * time.delta_secs();
+
+    for (transform, rotation, ports, mut tanks) in q_buildings.iter_mut() {
+        for port in ports.0.iter() {
+            let tile = port_tile(transform.translation, *rotation, port.offset);
+            let Some(index) = networks.index_at(tile) else {
+                continue;
+            };
+
+            let network = &mut networks.networks[index];
+            if network.mixed {
+                continue;
+            }
+
+            let Some(tank) = tanks.0.get_mut(port.tank) else {
+                continue;
+            };
+
+            // Ports that go both ways push towards whichever side is emptier
+            let pushing = match port.flow {
+                PortFlow::Output => true,
+                PortFlow::Input => false,
+                PortFlow::Both => tank.fill_ratio() > network.fill_ratio(),
+            };
+
+            let limit = match port.flow {
+                PortFlow::Both => {
+                    let difference = (tank.fill_ratio() - network.fill_ratio()).abs();
+                    budget.min(difference * tank.capacity.min(network.capacity) / 2.0)
+                }
+                _ => budget,
+            };
+
+            if pushing {
+                let Some(fluid) = tank.fluid else {
+                    continue;
+                };
+
+                if let Some(existing) = network.fluid
+                    && existing != fluid
+                    && network.amount > f32::EPSILON
+                {
+                    network.error = Some(FluidMixError {
+                        existing,
+                        incoming: fluid,
+                    });
+                    continue;
+                }
+
+                let moved = tank.take(limit.min(network.free_space()));
+                if moved > 0.0 {
+                    network.fluid = Some(fluid);
+                    network.amount += moved;
+                    network.flow += moved / time.delta_secs();
+                }
+            } else {
+                let Some(fluid) = network.fluid else {
+                    continue;
+                };
+
+                if network.amount <= f32::EPSILON {
+                    continue;
+                }
+
+                match tank.insert(fluid, limit.min(network.amount)) {
+                    Ok(moved) => {
+                        network.amount -= moved;
+                        if network.amount <= f32::EPSILON {
+                            network.amount = 0.0;
+                            network.fluid = None;
+                        }
+                    }
+                    Err(error) => network.error = Some(error),
+                }
+            }
+        }
+    }
+}
+
+pub fn transfer_device_fluids(
+    time: Res<Time>,
+    mut networks: ResMut<PipeNetworks>,
+    mut q_devices: Query<(
+        &Transform,
+        &BuildingRotation,
+        &FlowDevice,
+        Option<&PowerConsumer>,
+        Option<&mut BuildingStatus>,
+    )>,
+) {
+    for (transform, rotation, device, power, status) in q_devices.iter_mut() {
+        let new_status = move_device_fluid(
+            &mut networks,
+            time.delta_secs(),
+            transform,
+            *rotation,
+            *device,
+            power,
+        );
+
+        if let Some(mut status) = status {
+            *status = new_status;
+        }
+    }
+}
+
+fn move_device_fluid(
+    networks: &mut PipeNetworks,
+    delta_secs: f32,
+    transform: &Transform,
+    rotation: BuildingRotation,
+    device: FlowDevice,
+    power: Option<&PowerConsumer>,
+) -> BuildingStatus {
+    let back = port_tile(transform.translation, rotation, Vec2::NEG_X);
+    let front = port_tile(transform.translation, rotation, Vec2::X);
+
+    let (Some(from), Some(to)) = (networks.index_at(back), networks.index_at(front)) else {
+        return BuildingStatus::Idle;
+    };
+
+    // Both ends on the same network, nothing to do
+    if from == to {
+        return BuildingStatus::Idle;
+    }
+
+    let source = &networks.networks[from];
+    let target = &networks.networks[to];
+
+    if source.mixed || target.mixed {
+        return BuildingStatus::Idle;
+    }
+
+    let Some(fluid) = source.fluid else {
+        return BuildingStatus::InputStarved;
+    };
+
+    let wanted = match device {
+        FlowDevice::Pump { rate } => {
+            let speed = power.map(|power| power.speed()).unwrap_or(1.0);
+            rate * speed * delta_secs
+        }
+        FlowDevice::CheckValve { rate } => {
+            let difference = source.fill_ratio() - target.fill_ratio();
+            if difference <= 0.0 {
+                return BuildingStatus::Idle;
+            }
+            (rate * delta_secs).min(difference * source.capacity.min(target.capacity) / 2.0)
+        }
+        FlowDevice::OverflowValve { rate, threshold } => {
+            let excess = source.fill_ratio() - threshold;
+            if excess <= 0.0 {
+                return BuildingStatus::Idle;
+            }
+            (rate * delta_secs).min(excess * source.capacity)
+        }
+    };
+
+    if let Some(existing) = target.fluid
+        && existing != fluid
+        && target.amount > f32::EPSILON
+    {
+        networks.networks[to].error = Some(FluidMixError {
+            existing,
+            incoming: fluid,
+        });
+        return BuildingStatus::OutputBlocked;
+    }
+
+    let moved = wanted.min(source.amount).min(target.free_space());
+    if moved <= 0.0 {
+        return if target.free_space() <= 0.0 {
+            BuildingStatus::OutputBlocked
+        } else {
+            BuildingStatus::Idle
+        };
+    }
+
+    let source = &mut networks.networks[from];
+    source.amount -= moved;
+    if source.amount <= f32::EPSILON {
+        source.amount = 0.0;
+        source.fluid = None;
+    }
+
+    let target = &mut networks.networks[to];
+    target.amount += moved;
+    target.flow += moved / delta_secs;
+    target.fluid = Some(fluid);
+
+    BuildingStatus::Working
+}
+
+pub fn distribute_network_fluid(
+    networks: Res<PipeNetworks>,
+    mut q_tanks: Query<&mut FluidTank, With<Pipe>>,
+) {
+    for network in networks.networks.iter() {
+        // Mixed networks keep whatever each pipe holds until flushed
+        if network.mixed {
+            continue;
+        }
+
+        let ratio = network.fill_ratio();
+        for pipe in network.pipes.iter() {
+            if let Ok(mut tank) = q_tanks.get_mut(*pipe) {
+                if network.fluid.is_some() && ratio > 0.0 {
+                    tank.fluid = network.fluid;
+                    tank.amount = ratio * tank.capacity;
+                } else {
+                    tank.clear();
+                }
+            }
+        }
+    }
+}
+
+pub fn flush_pipe_networks(
+    mut msg_reader: MessageReader<FlushPipeNetworkMsg>,
+    networks: Res<PipeNetworks>,
+    mut q_tanks: Query<&mut FluidTank, With<Pipe>>,
+) {
+    for msg in msg_reader.read() {
+        let Some(network) = networks.at(msg.tile) else {
+            continue;
+        };
+
+        for pipe in network.pipes.iter() {
+            if let Ok(mut tank) = q_tanks.get_mut(*pipe) {
+                tank.clear();
+            }
+        }
+    }
+}
+
+pub fn tint_pipes_by_fluid(
+    time: Res<Time>,
+    networks: Res<PipeNetworks>,
+    mut q_pipes: Query<&mut Sprite, With<Pipe>>,
+) {
+    // Networks with an error blink red so they can be found on the map
+    let blink_on = (time.elapsed_secs() * 3.0) as u32 % 2 == 0;
+
+    for network in networks.networks.iter() {
+        let color = if network.error.is_some() && blink_on {
+            Color::linear_rgb(4.0, 0.4, 0.4)
+        } else {
+            network
+                .fluid
+                .map(|fluid| fluid.tint())
+                .unwrap_or(Color::WHITE)
+        };
+
+        for pipe in network.pipes.iter() {
+            if let Ok(mut sprite) = q_pipes.get_mut(*pipe) {
+                sprite.color = color;
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::construction::ConstructionQueue;
+use crate::inventory::Inventory;
+use crate::research::Research;
+use crate::stats::ProductionStats;
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .add_computed_state::<InWorld>()
+            .init_resource::<MapSettings>()
+            .init_resource::<Settings>()
+            .add_systems(OnEnter(GameState::Loading), reset_game_resources)
+            .add_systems(Update, finish_loading.run_if(in_state(GameState::Loading)))
+            .add_systems(Update, toggle_pause.run_if(in_state(InWorld)))
+            .add_systems(OnEnter(GameState::Paused), pause_time)
+            .add_systems(OnExit(GameState::Paused), resume_time)
+            .add_systems(OnExit(InWorld), clear_world);
+    }
+}
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    // The map is being built and its textures loaded
+    Loading,
+    InGame,
+    Paused,
+}
+
+/// Active while a map exists, paused or not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InWorld;
+
+impl ComputedStates for InWorld {
+    type SourceStates = GameState;
+
+    fn compute(state: GameState) -> Option<Self> {
+        match state {
+            GameState::InGame | GameState::Paused => Some(InWorld),
+            GameState::MainMenu | GameState::Loading => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl MapSize {
+    pub const ALL: [MapSize; 3] = [MapSize::Small, MapSize::Medium, MapSize::Large];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapSize::Small => "Small (32x32)",
+            MapSize::Medium => "Medium (64x64)",
+            MapSize::Large => "Large (128x128)",
+        }
+    }
+
+    /// Name used by save files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            MapSize::Small => "small",
+            MapSize::Medium => "medium",
+            MapSize::Large => "large",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<MapSize> {
+        MapSize::ALL.into_iter().find(|size| size.id() == id)
+    }
+
+    pub fn tiles(&self) -> u32 {
+        match self {
+            MapSize::Small => 32,
+            MapSize::Medium => 64,
+            MapSize::Large => 128,
+        }
+    }
+}
+
+/// Chosen in the main menu before a new game starts.
+#[derive(Resource)]
+pub struct MapSettings {
+    pub seed: u64,
+    pub size: MapSize,
+}
+
+impl Default for MapSettings {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            size: MapSize::Small,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Settings {
+    // Multiplies the camera's own pan speed
+    pub pan_speed: f32,
+    // Pan when the cursor touches the window border
+    pub edge_scroll: bool,
+    pub ui_scale: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            pan_speed: 1.0,
+            edge_scroll: true,
+            ui_scale: 1.0,
+        }
+    }
+}
+
+// Everything a previous game left behind starts over
+fn reset_game_resources(mut commands: Commands) {
+    commands.insert_resource(Research::default());
+    commands.insert_resource(Inventory::default());
+    commands.insert_resource(ProductionStats::default());
+    commands.insert_resource(ConstructionQueue::default());
+}
+
+fn finish_loading(
+    asset_server: Res<AssetServer>,
+    q_tilemaps: Query<&TilemapTexture>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // Give up waiting on textures that failed, the game still runs without them
+    let loaded = q_tilemaps.iter().all(|texture| match texture {
+        TilemapTexture::Single(handle) => matches!(
+            asset_server.get_load_state(handle.id()),
+            Some(bevy::asset::LoadState::Loaded | bevy::asset::LoadState::Failed(_))
+        ),
+        _ => true,
+    });
+
+    if loaded && !q_tilemaps.is_empty() {
+        next_state.set(GameState::InGame);
+    }
+}
+
+fn toggle_pause(
+    actions: Actions,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::InGame),
+        _ => {}
+    }
+}
+
+// The simulation runs on virtual time, the camera does not
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Despawns the map and everything built on it.
+pub fn clear_world(
+    mut commands: Commands,
+    q_roots: Query<Entity, (With<Transform>, Without<ChildOf>, Without<Camera>)>,
+    // Unbuilt and half deconstructed buildings are disabled and need asking for
+    q_disabled: Query<Entity, (With<Disabled>, Without<ChildOf>)>,
+    q_tiles: Query<Entity, With<TilePos>>,
+) {
+    for entity in q_roots
+        .iter()
+        .chain(q_disabled.iter())
+        .chain(q_tiles.iter())
+    {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,214 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::bindings::{Action, Actions};
+use crate::buildings::helpers::BuildingKind;
+use crate::buildings::oil_container::{LargeOilContainer, MediumOilContainer, SmallOilContainer};
+use crate::fluids::{Fluid, FluidTanks};
+use crate::items::ItemStorage;
+use crate::stats::Material;
+
+// How long a refused placement stays on screen
+const REFUSAL_SECS: f32 = 3.0;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inventory>()
+            .init_resource::<PlacementRefusal>()
+            .add_systems(Update, tick_placement_refusal);
+    }
+}
+
+/// What the player carries. Pays for construction before central storage is
+/// touched and receives the refunds of deleted buildings.
+#[derive(Resource)]
+pub struct Inventory {
+    amounts: HashMap<Material, f32>,
+}
+
+impl Default for Inventory {
+    // Enough to get the first extractors and a container going
+    fn default() -> Self {
+        Self {
+            amounts: HashMap::from([(Material::Fluid(Fluid::CrudeOil), 400.0)]),
+        }
+    }
+}
+
+impl Inventory {
+    pub fn amount(&self, material: Material) -> f32 {
+        self.amounts.get(&material).copied().unwrap_or(0.0)
+    }
+
+    pub fn set(&mut self, material: Material, amount: f32) {
+        self.amounts.insert(material, amount);
+    }
+
+    pub fn add(&mut self, material: Material, amount: f32) {
+        *self.amounts.entry(material).or_insert(0.0) += amount;
+    }
+
+    /// Removes up to `amount`, returning how much was taken.
+    pub fn take(&mut self, material: Material, amount: f32) -> f32 {
+        let Some(held) = self.amounts.get_mut(&material) else {
+            return 0.0;
+        };
+
+        let taken = amount.min(*held);
+        *held -= taken;
+        taken
+    }
+}
+
+/// The inventory together with central storage: fluids in oil containers and
+/// items in any building's storage.
+#[derive(SystemParam)]
+pub struct Stockpile<'w, 's> {
+    pub inventory: ResMut<'w, Inventory>,
+    containers: Query<
+        'w,
+        's,
+        &'static mut FluidTanks,
+        Or<(
+            With<SmallOilContainer>,
+            With<MediumOilContainer>,
+            With<LargeOilContainer>,
+        )>,
+    >,
+    storages: Query<'w, 's, &'static mut ItemStorage>,
+}
+
+impl Stockpile<'_, '_> {
+    /// Everything stored outside the inventory.
+    pub fn stored(&self, material: Material) -> f32 {
+        match material {
+            Material::Fluid(fluid) => self
+                .containers
+                .iter()
+                .flat_map(|tanks| tanks.0.iter())
+                .filter(|tank| tank.fluid == Some(fluid))
+                .map(|tank| tank.amount)
+                .sum(),
+            Material::Item(item) => self
+                .storages
+                .iter()
+                .map(|storage| storage.count(item) as f32)
+                .sum(),
+        }
+    }
+
+    pub fn available(&self, material: Material) -> f32 {
+        self.inventory.amount(material) + self.stored(material)
+    }
+
+    /// Removes up to `amount`, inventory first, returning how much was taken.
+    /// Items only come out of storage whole, so slightly more than asked may be taken.
+    pub fn take(&mut self, material: Material, amount: f32) -> f32 {
+        let mut taken = self.inventory.take(material, amount);
+
+        match material {
+            Material::Fluid(fluid) => {
+                for mut tanks in self.containers.iter_mut() {
+                    for tank in tanks.0.iter_mut() {
+                        if tank.fluid == Some(fluid) && taken < amount {
+                            taken += tank.take(amount - taken);
+                        }
+                    }
+                }
+            }
+            Material::Item(item) => {
+                for mut storage in self.storages.iter_mut() {
+                    while taken < amount && storage.take(item) {
+                        taken += 1.0;
+                    }
+                }
+            }
+        }
+
+        taken
+    }
+
+    pub fn check(&self, cost: &[(Material, f32)]) -> Result<(), String> {
+        for (material, amount) in cost.iter() {
+            let available = self.available(*material);
+            if available < *amount {
+                return Err(format!(
+                    "Not enough {}: need {:.0}, have {:.0}",
+                    material, amount, available
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pays the whole cost or nothing at all.
+    pub fn pay(&mut self, cost: &[(Material, f32)]) -> Result<(), String> {
+        self.check(cost)?;
+
+        for (material, amount) in cost.iter() {
+            self.take(*material, *amount);
+        }
+
+        Ok(())
+    }
+}
+
+/// Why the last placement was refused, shown for a few seconds.
+#[derive(Resource, Default)]
+pub struct PlacementRefusal {
+    pub reason: Option<String>,
+    timer: f32,
+}
+
+impl PlacementRefusal {
+    pub fn refuse(&mut self, reason: String) {
+        self.reason = Some(reason);
+        self.timer = REFUSAL_SECS;
+    }
+}
+
+fn tick_placement_refusal(time: Res<Time>, mut refusal: ResMut<PlacementRefusal>) {
+    if refusal.reason.is_none() {
+        return;
+    }
+
+    refusal.timer -= time.delta_secs();
+    if refusal.timer <= 0.0 {
+        refusal.reason = None;
+    }
+}
+
+/// Used by the placement systems before a construction site is laid down.
+#[derive(SystemParam)]
+pub struct PlacementCheck<'w, 's> {
+    stockpile: Stockpile<'w, 's>,
+    refusal: ResMut<'w, PlacementRefusal>,
+    actions: Actions<'w>,
+}
+
+impl PlacementCheck<'_, '_> {
+    /// Whether `kind` may be placed. Sites are only paid for once construction
+    /// starts, so holding the plan key places one ahead of having the materials.
+    pub fn allow(&mut self, kind: BuildingKind) -> bool {
+        if self.actions.pressed(Action::Plan) {
+            return true;
+        }
+
+        match self.stockpile.check(kind.cost()) {
+            Ok(()) => true,
+            Err(reason) => {
+                self.refusal.refuse(format!(
+                    "Can't build {}. {} (hold {} to plan it anyway)",
+                    kind.name(),
+                    reason,
+                    self.actions.label(Action::Plan)
+                ));
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_items);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Item {
+    Plastic,
+    Sulfur,
+}
+
+impl Item {
+    pub const ALL: [Item; 2] = [Item::Plastic, Item::Sulfur];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Item::Plastic => "Plastic",
+            Item::Sulfur => "Sulfur",
+        }
+    }
+
+    pub fn atlas_index(&self) -> usize {
+        match self {
+            Item::Plastic => 0,
+            Item::Sulfur => 1,
+        }
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Resource)]
+pub struct ItemAssets {
+    pub texture: Handle<Image>,
+    pub atlas_layout: Handle<TextureAtlasLayout>,
+}
+
+/// Solid items held by a building, shared between all item types.
+#[derive(Component, Clone, Debug, Default)]
+pub struct ItemStorage {
+    pub items: HashMap<Item, u32>,
+    pub capacity: u32,
+}
+
+impl ItemStorage {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            items: HashMap::new(),
+            capacity,
+        }
+    }
+
+    pub fn count(&self, item: Item) -> u32 {
+        self.items.get(&item).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.items.values().sum()
+    }
+
+    pub fn free_space(&self) -> u32 {
+        self.capacity.saturating_sub(self.total())
+    }
+
+    /// Returns false if the storage is full.
+    pub fn insert(&mut self, item: Item) -> bool {
+        if self.free_space() == 0 {
+            return false;
+        }
+
+        *self.items.entry(item).or_insert(0) += 1;
+        true
+    }
+
+    pub fn take(&mut self, item: Item) -> bool {
+        match self.items.get_mut(&item) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Goes through `Item::ALL` so the order items come out in is stable
+    pub fn take_any(&mut self) -> Option<Item> {
+        Item::ALL.into_iter().find(|item| self.take(*item))
+    }
+}
+
+fn setup_items(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let texture = asset_server.load("textures/items.png");
+
+    // Create texture atlas layout for 32x16 spritesheet with 2 items of 16x16
+    let layout = TextureAtlasLayout::from_grid(UVec2::new(16, 16), 2, 1, None, None);
+    let atlas_layout = texture_atlases.add(layout);
+
+    commands.insert_resource(ItemAssets {
+        texture,
+        atlas_layout,
+    });
+}
+
+pub fn item_sprite(assets: &ItemAssets, item: Item) -> Sprite {
+    Sprite {
+        image: assets.texture.clone(),
+        texture_atlas: Some(TextureAtlas {
+            layout: assets.atlas_layout.clone(),
+            index: item.atlas_index(),
+        }),
+        ..default()
+    }
+}
@@ -1,48 +1,68 @@
+use crate::alerts::AlertsPlugin;
+use crate::animation::AnimationPlugin;
+use crate::bindings::BindingsPlugin;
+use crate::buildings::conveyor::ConveyorPlugin;
+use crate::buildings::gas_generator::GasGeneratorPlugin;
+use crate::buildings::inserter::InserterPlugin;
 use crate::buildings::oil_container::OilContainerPlugin;
 use crate::buildings::oil_extractor::OilExtractorPlugin;
 use crate::buildings::oil_refinery::OilRefineryPlugin;
 use crate::buildings::pipe::PipePlugin;
+use crate::buildings::placement::PlacementPlugin;
+use crate::buildings::power_pole::PowerPolePlugin;
+use crate::buildings::pump::PumpPlugin;
+use crate::buildings::valve::ValvePlugin;
+use crate::camera::CameraPlugin;
+use crate::console::ConsolePlugin;
+use crate::construction::ConstructionPlugin;
+use crate::fluids::FluidsPlugin;
+use crate::game::GamePlugin;
+use crate::inventory::InventoryPlugin;
+use crate::items::ItemsPlugin;
+use crate::overlay::UtilizationOverlayPlugin;
+use crate::pollution::PollutionPlugin;
+use crate::power::PowerPlugin;
+use crate::research::ResearchPlugin;
+use crate::save::SavePlugin;
+use crate::stats::StatsPlugin;
+use crate::tiles::TerrainPlugin;
 use crate::tiles::picking::TilemapBackendPlugin;
+use crate::ui::alerts::AlertsEguiPlugin;
+use crate::ui::console::ConsoleEguiPlugin;
 use crate::ui::debug::DebugEguiPlugin;
-use bevy::{input::mouse::MouseWheel, math::ops::powf, prelude::*};
+use crate::ui::hover::HoverTooltipEguiPlugin;
+use crate::ui::inventory::InventoryEguiPlugin;
+use crate::ui::menu::MenuEguiPlugin;
+use crate::ui::minimap::MinimapEguiPlugin;
+use crate::ui::networks::PipeNetworksEguiPlugin;
+use crate::ui::power::PowerGridsEguiPlugin;
+use crate::ui::research::ResearchEguiPlugin;
+use crate::ui::stats::ProductionStatsEguiPlugin;
+use crate::ui::toolbar::BuildToolbarEguiPlugin;
+use bevy::prelude::*;
 use bevy_ecs_tilemap::TilemapPlugin;
 use bevy_egui::EguiPlugin;
 
+mod alerts;
+mod animation;
+mod bindings;
 mod buildings;
+mod camera;
+mod console;
+mod construction;
+mod fluids;
+mod game;
+mod inventory;
+mod items;
+mod overlay;
+mod pollution;
+mod power;
+mod research;
+mod save;
+mod stats;
 mod tiles;
 mod ui;
 
-#[derive(Component)]
-struct Speed(f32);
-
-#[derive(Component)]
-struct AnimationIndices {
-    first: usize,
-    last: usize,
-}
-
-#[derive(Component, Deref, DerefMut)]
-struct AnimationTimer(Timer);
-
-#[derive(Component)]
-struct Camera {
-    speed: Speed,
-
-    max_zoom: f32,
-    min_zoom: f32,
-}
-
-impl Default for Camera {
-    fn default() -> Self {
-        Self {
-            speed: Speed(300.0),
-
-            max_zoom: 4.0,
-            min_zoom: 0.3,
-        }
-    }
-}
-
 fn main() {
     App::new()
         .add_plugins(
@@ -56,77 +76,56 @@ fn main() {
                 })
                 .set(ImagePlugin::default_nearest()),
         )
-        .add_plugins((TilemapPlugin, TilemapBackendPlugin))
-        .add_plugins((EguiPlugin::default(), DebugEguiPlugin))
         .add_plugins((
+            TilemapPlugin,
+            TilemapBackendPlugin,
+            TerrainPlugin,
+            PollutionPlugin,
+        ))
+        .add_plugins((
+            EguiPlugin::default(),
+            MenuEguiPlugin,
+            BuildToolbarEguiPlugin,
+            ConsoleEguiPlugin,
+            DebugEguiPlugin,
+            PipeNetworksEguiPlugin,
+            PowerGridsEguiPlugin,
+            ProductionStatsEguiPlugin,
+            AlertsEguiPlugin,
+            ResearchEguiPlugin,
+            InventoryEguiPlugin,
+            MinimapEguiPlugin,
+            HoverTooltipEguiPlugin,
+        ))
+        .add_plugins((
+            PlacementPlugin,
             PipePlugin,
             OilExtractorPlugin,
             OilContainerPlugin,
             OilRefineryPlugin,
+            PumpPlugin,
+            ValvePlugin,
+            ConveyorPlugin,
+            InserterPlugin,
+            PowerPolePlugin,
+            GasGeneratorPlugin,
+        ))
+        .add_plugins((
+            GamePlugin,
+            BindingsPlugin,
+            CameraPlugin,
+            FluidsPlugin,
+            ItemsPlugin,
+            PowerPlugin,
+            StatsPlugin,
+            UtilizationOverlayPlugin,
+            AlertsPlugin,
+            AnimationPlugin,
+            ResearchPlugin,
+            InventoryPlugin,
+            ConstructionPlugin,
+            ConsolePlugin,
+            SavePlugin,
         ))
-        .add_systems(Startup, spawn_camera)
-        .add_systems(Update, camera_controls)
-        .add_systems(Update, animate_sprite)
-        .add_systems(Startup, tiles::tiles_startup)
         .run();
 }
-
-fn animate_sprite(
-    time: Res<Time>,
-    mut query: Query<(&AnimationIndices, &mut AnimationTimer, &mut Sprite)>,
-) {
-    for (indices, mut timer, mut sprite) in &mut query {
-        timer.tick(time.delta());
-
-        if timer.just_finished()
-            && let Some(atlas) = &mut sprite.texture_atlas
-        {
-            atlas.index = if atlas.index == indices.last {
-                indices.first
-            } else {
-                atlas.index + 1
-            };
-        }
-    }
-}
-
-fn spawn_camera(mut commands: Commands) {
-    commands.spawn((Camera2d, Camera::default()));
-}
-
-fn camera_controls(
-    q_camera: Single<(&mut Camera, &mut Transform, &mut Projection)>,
-    input: Res<ButtonInput<KeyCode>>,
-    mut wheel_msg: MessageReader<MouseWheel>,
-    time: Res<Time<Fixed>>,
-) {
-    let (camera, mut transform, mut projection) = q_camera.into_inner();
-
-    let speed = camera.speed.0 * time.delta_secs();
-
-    if input.pressed(KeyCode::KeyW) {
-        transform.translation.y += speed;
-    }
-    if input.pressed(KeyCode::KeyS) {
-        transform.translation.y -= speed;
-    }
-    if input.pressed(KeyCode::KeyA) {
-        transform.translation.x -= speed;
-    }
-    if input.pressed(KeyCode::KeyD) {
-        transform.translation.x += speed;
-    }
-
-    if let Projection::Orthographic(projection2d) = &mut *projection {
-        for msg in wheel_msg.read() {
-            if msg.y > 0.0 {
-                projection2d.scale *= powf(4.0f32, time.delta_secs());
-            }
-            if msg.y < 0.0 {
-                projection2d.scale *= powf(0.25f32, time.delta_secs());
-            }
-        }
-
-        projection2d.scale = projection2d.scale.clamp(camera.min_zoom, camera.max_zoom);
-    }
-}
@@ -0,0 +1,147 @@
+use bevy::color::Mix;
+use bevy::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::buildings::helpers::{Building, BuildingStatus, Footprint, TILE_SIZE, world_to_grid};
+use crate::buildings::pipe::Pipe;
+use crate::fluids::network::PipeNetworks;
+
+pub struct UtilizationOverlayPlugin;
+
+impl Plugin for UtilizationOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UtilizationOverlay>().add_systems(
+            Update,
+            (
+                toggle_utilization_overlay,
+                spawn_overlay_tints,
+                color_overlay_tints,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Colors pipes by how much they carry and buildings by what they are doing.
+#[derive(Resource, Default)]
+pub struct UtilizationOverlay {
+    pub active: bool,
+}
+
+const OVERLAY_ALPHA: f32 = 0.55;
+
+// Drawn above the building sprite and its rotation indicator
+const OVERLAY_Z: f32 = 2.0;
+
+#[derive(Component)]
+struct OverlayTint;
+
+// Marks buildings that already have a tint child
+#[derive(Component)]
+struct Tinted;
+
+pub fn status_color(status: BuildingStatus) -> Color {
+    match status {
+        BuildingStatus::Working => Color::srgb_u8(145, 219, 105),
+        BuildingStatus::Idle => Color::srgb_u8(155, 171, 178),
+        BuildingStatus::OutputBlocked => Color::srgb_u8(251, 185, 84),
+        BuildingStatus::InputStarved => Color::srgb_u8(230, 70, 60),
+        BuildingStatus::Depleted => Color::srgb_u8(180, 140, 100),
+        BuildingStatus::NoDeposit => Color::srgb_u8(110, 110, 120),
+    }
+}
+
+// Quiet blue for idle pipes, red once they carry all they can
+fn utilization_color(utilization: f32) -> Color {
+    let low = Color::srgb_u8(77, 101, 180);
+    let mid = Color::srgb_u8(145, 219, 105);
+    let high = Color::srgb_u8(230, 70, 60);
+
+    if utilization < 0.5 {
+        low.mix(&mid, utilization * 2.0)
+    } else {
+        mid.mix(&high, (utilization - 0.5) * 2.0)
+    }
+}
+
+fn toggle_utilization_overlay(actions: Actions, mut overlay: ResMut<UtilizationOverlay>) {
+    if actions.just_pressed(Action::ToggleOverlay) {
+        overlay.active = !overlay.active;
+    }
+}
+
+fn spawn_overlay_tints(
+    mut commands: Commands,
+    overlay: Res<UtilizationOverlay>,
+    q_buildings: Query<
+        (Entity, Option<&Footprint>),
+        (
+            With<Building>,
+            Or<(With<BuildingStatus>, With<Pipe>)>,
+            Without<Tinted>,
+        ),
+    >,
+    q_tinted: Query<Entity, With<Tinted>>,
+    q_tints: Query<Entity, With<OverlayTint>>,
+) {
+    if !overlay.active {
+        if overlay.is_changed() {
+            for tint in q_tints.iter() {
+                commands.entity(tint).despawn();
+            }
+            for building in q_tinted.iter() {
+                commands.entity(building).remove::<Tinted>();
+            }
+        }
+        return;
+    }
+
+    for (building, footprint) in q_buildings.iter() {
+        let size = footprint.map(|f| f.0).unwrap_or(1) as f32 * TILE_SIZE;
+
+        commands.entity(building).insert(Tinted);
+        commands.spawn((
+            OverlayTint,
+            Sprite {
+                color: Color::NONE,
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            Transform::from_xyz(0.0, 0.0, OVERLAY_Z),
+            Pickable::IGNORE,
+            ChildOf(building),
+        ));
+    }
+}
+
+fn color_overlay_tints(
+    overlay: Res<UtilizationOverlay>,
+    networks: Res<PipeNetworks>,
+    q_buildings: Query<(&Transform, Option<&BuildingStatus>, Has<Pipe>), With<Tinted>>,
+    mut q_tints: Query<(&ChildOf, &mut Sprite), With<OverlayTint>>,
+) {
+    if !overlay.active {
+        return;
+    }
+
+    for (child_of, mut sprite) in q_tints.iter_mut() {
+        let Ok((transform, status, is_pipe)) = q_buildings.get(child_of.parent()) else {
+            continue;
+        };
+
+        let color = if is_pipe {
+            match networks.at(world_to_grid(transform.translation)) {
+                // Mixed networks move nothing, show them as starved
+                Some(network) if network.error.is_some() => {
+                    status_color(BuildingStatus::InputStarved)
+                }
+                Some(network) => utilization_color(network.utilization()),
+                None => utilization_color(0.0),
+            }
+        } else {
+            status_color(status.copied().unwrap_or_default())
+        };
+
+        sprite.color = color.with_alpha(OVERLAY_ALPHA);
+    }
+}
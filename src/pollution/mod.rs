@@ -0,0 +1,193 @@
+use bevy::color::Mix;
+use bevy::picking::hover::PickingInteraction;
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::buildings::helpers::{BuildingStatus, world_to_grid};
+use crate::game::{GameState, InWorld, MapSettings};
+use crate::tiles::TerrainLayer;
+use crate::tiles::terrain::TerrainMap;
+
+// Pollution spreads and soaks away in steps this far apart, in seconds
+const POLLUTION_TICK: f32 = 0.5;
+
+// Share of a tile's pollution handed to each neighbour every step
+const DIFFUSION: f32 = 0.1;
+
+// Pollution at which the heatmap is fully red
+const HEATMAP_MAX: f32 = 50.0;
+
+pub struct PollutionPlugin;
+
+impl Plugin for PollutionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Pollution>()
+            .init_resource::<PollutionOverlay>()
+            .add_systems(OnEnter(GameState::Loading), reset_pollution)
+            .add_systems(
+                Update,
+                (
+                    toggle_pollution_overlay,
+                    spread_pollution,
+                    color_pollution_heatmap,
+                )
+                    .chain()
+                    .run_if(in_state(InWorld)),
+            );
+    }
+}
+
+/// Pollution given off per second by a building while it is working.
+#[derive(Component)]
+pub struct PollutionEmitter(pub f32);
+
+/// How much pollution sits on each tile, indexed like `TerrainMap`.
+#[derive(Resource, Default)]
+pub struct Pollution {
+    size: u32,
+    values: Vec<f32>,
+}
+
+impl Pollution {
+    fn new(size: u32) -> Self {
+        Self {
+            size,
+            values: vec![0.0; (size * size) as usize],
+        }
+    }
+
+    pub fn at(&self, x: u32, y: u32) -> f32 {
+        self.values
+            .get((y * self.size + x) as usize)
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+/// Tints the ground by how polluted it is.
+#[derive(Resource, Default)]
+pub struct PollutionOverlay {
+    pub active: bool,
+}
+
+fn reset_pollution(mut commands: Commands, map_settings: Res<MapSettings>) {
+    commands.insert_resource(Pollution::new(map_settings.size.tiles()));
+}
+
+fn toggle_pollution_overlay(actions: Actions, mut overlay: ResMut<PollutionOverlay>) {
+    if actions.just_pressed(Action::TogglePollution) {
+        overlay.active = !overlay.active;
+    }
+}
+
+fn spread_pollution(
+    time: Res<Time>,
+    terrain: Res<TerrainMap>,
+    mut pollution: ResMut<Pollution>,
+    q_emitters: Query<(&Transform, &PollutionEmitter, &BuildingStatus)>,
+    mut elapsed: Local<f32>,
+) {
+    *elapsed += time.delta_secs();
+    if *elapsed < POLLUTION_TICK {
+        return;
+    }
+    *elapsed -= POLLUTION_TICK;
+
+    // The field is replaced along with the map while loading
+    let size = pollution.size;
+    if size != terrain.size {
+        return;
+    }
+
+    for (transform, emitter, status) in q_emitters.iter() {
+        if *status != BuildingStatus::Working {
+            continue;
+        }
+        if let Some((x, y)) = terrain.grid_to_tile(world_to_grid(transform.translation)) {
+            pollution.values[(y * size + x) as usize] += emitter.0 * POLLUTION_TICK;
+        }
+    }
+
+    let mut next = pollution.values.clone();
+    for y in 0..size {
+        for x in 0..size {
+            let index = (y * size + x) as usize;
+            let share = pollution.values[index] * DIFFUSION;
+            if share <= 0.0 {
+                continue;
+            }
+
+            // Nothing leaks off the edge of the map
+            let neighbours = [
+                (x > 0).then(|| index - 1),
+                (x + 1 < size).then(|| index + 1),
+                (y > 0).then(|| index - size as usize),
+                (y + 1 < size).then(|| index + size as usize),
+            ];
+            for neighbour in neighbours.into_iter().flatten() {
+                next[neighbour] += share;
+                next[index] -= share;
+            }
+        }
+    }
+
+    for y in 0..size {
+        for x in 0..size {
+            let absorbed = terrain.ground(x, y).pollution_absorption() * POLLUTION_TICK;
+            let value = &mut next[(y * size + x) as usize];
+            *value = (*value - absorbed).max(0.0);
+        }
+    }
+
+    pollution.values = next;
+}
+
+fn heatmap_color(pollution: f32) -> Color {
+    Color::WHITE.mix(
+        &Color::srgb_u8(230, 70, 60),
+        (pollution / HEATMAP_MAX).min(1.0),
+    )
+}
+
+// Hovered tiles keep their highlight, they pick the heatmap up again on the next step
+fn color_pollution_heatmap(
+    overlay: Res<PollutionOverlay>,
+    pollution: Res<Pollution>,
+    q_layers: Query<(&TerrainLayer, &TileStorage)>,
+    mut q_tiles: Query<(&mut TileColor, Option<&PickingInteraction>)>,
+    // Whether the heatmap is currently painted on, the toolbar touches the overlay every frame
+    mut shown: Local<bool>,
+) {
+    let toggled = overlay.active != *shown;
+    if !toggled && !(overlay.active && pollution.is_changed()) {
+        return;
+    }
+    *shown = overlay.active;
+
+    for (layer, storage) in &q_layers {
+        if *layer != TerrainLayer::Ground {
+            continue;
+        }
+
+        for y in 0..pollution.size {
+            for x in 0..pollution.size {
+                let Some(tile) = storage.get(&TilePos { x, y }) else {
+                    continue;
+                };
+                let Ok((mut color, interaction)) = q_tiles.get_mut(tile) else {
+                    continue;
+                };
+                if interaction.is_some_and(|i| *i != PickingInteraction::None) {
+                    continue;
+                }
+
+                color.0 = if overlay.active {
+                    heatmap_color(pollution.at(x, y))
+                } else {
+                    Color::WHITE
+                };
+            }
+        }
+    }
+}
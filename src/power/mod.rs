@@ -0,0 +1,222 @@
+use crate::buildings::helpers::{Building, TILE_SIZE};
+use bevy::prelude::*;
+
+pub struct PowerPlugin;
+
+impl Plugin for PowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PowerGrids>().add_systems(
+            Update,
+            (rebuild_power_grids, balance_power_grids, draw_power_wires).chain(),
+        );
+    }
+}
+
+// Poles this close together (in tiles) share a grid
+pub const POLE_CONNECTION_RADIUS: f32 = 6.0;
+
+// Buildings this close to a pole (in tiles) are connected to its grid
+pub const POLE_SUPPLY_RADIUS: f32 = 3.0;
+
+#[derive(Component)]
+pub struct PowerPole;
+
+/// Produces up to `max_output` kW while it has something to burn.
+#[derive(Component, Debug, Default)]
+pub struct PowerProducer {
+    pub max_output: f32,
+    pub fueled: bool,
+    // Set by the grid each frame, what the producer actually has to supply
+    pub output: f32,
+}
+
+impl PowerProducer {
+    pub fn new(max_output: f32) -> Self {
+        Self {
+            max_output,
+            ..default()
+        }
+    }
+
+    pub fn load(&self) -> f32 {
+        if self.max_output <= 0.0 {
+            0.0
+        } else {
+            self.output / self.max_output
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct PowerConsumer {
+    pub demand: f32,
+    // How fast the building still works with no power at all
+    pub min_speed: f32,
+    // Share of the demand the grid can cover, 0.0 when not connected
+    pub satisfaction: f32,
+}
+
+impl PowerConsumer {
+    pub fn new(demand: f32, min_speed: f32) -> Self {
+        Self {
+            demand,
+            min_speed,
+            satisfaction: 0.0,
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.min_speed + (1.0 - self.min_speed) * self.satisfaction
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PowerGrid {
+    pub poles: Vec<(Entity, Vec2)>,
+    pub producers: usize,
+    pub consumers: usize,
+    // All in kW
+    pub capacity: f32,
+    pub production: f32,
+    pub demand: f32,
+    pub satisfaction: f32,
+}
+
+/// Poles grouped into grids, rebuilt every frame.
+#[derive(Resource, Default)]
+pub struct PowerGrids {
+    pub grids: Vec<PowerGrid>,
+    // Pairs of connected pole positions, for drawing wires
+    pub wires: Vec<(Vec2, Vec2)>,
+}
+
+impl PowerGrids {
+    /// The grid whose closest pole covers `position`, if any.
+    pub fn grid_at(&self, position: Vec2) -> Option<usize> {
+        let reach = POLE_SUPPLY_RADIUS * TILE_SIZE;
+
+        self.grids
+            .iter()
+            .enumerate()
+            .flat_map(|(index, grid)| grid.poles.iter().map(move |(_, pole)| (index, *pole)))
+            .map(|(index, pole)| (index, pole.distance(position)))
+            .filter(|(_, distance)| *distance <= reach)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+}
+
+fn rebuild_power_grids(
+    mut grids: ResMut<PowerGrids>,
+    q_poles: Query<(Entity, &Transform), (With<PowerPole>, With<Building>)>,
+) {
+    let poles: Vec<(Entity, Vec2)> = q_poles
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation.truncate()))
+        .collect();
+
+    let reach = POLE_CONNECTION_RADIUS * TILE_SIZE;
+
+    grids.grids.clear();
+    grids.wires.clear();
+
+    // Flood fill over poles in reach of each other
+    let mut grid_of: Vec<Option<usize>> = vec![None; poles.len()];
+    for start in 0..poles.len() {
+        if grid_of[start].is_some() {
+            continue;
+        }
+
+        let index = grids.grids.len();
+        let mut grid = PowerGrid::default();
+        let mut stack = vec![start];
+        grid_of[start] = Some(index);
+
+        while let Some(current) = stack.pop() {
+            grid.poles.push(poles[current]);
+
+            for other in 0..poles.len() {
+                if other == current || poles[current].1.distance(poles[other].1) > reach {
+                    continue;
+                }
+
+                // Only record each wire once
+                if other > current {
+                    grids.wires.push((poles[current].1, poles[other].1));
+                }
+
+                if grid_of[other].is_none() {
+                    grid_of[other] = Some(index);
+                    stack.push(other);
+                }
+            }
+        }
+
+        grids.grids.push(grid);
+    }
+}
+
+fn balance_power_grids(
+    mut grids: ResMut<PowerGrids>,
+    mut q_producers: Query<(&Transform, &mut PowerProducer)>,
+    mut q_consumers: Query<(&Transform, &mut PowerConsumer)>,
+) {
+    // First total up supply and demand per grid
+    let producer_grids: Vec<Option<usize>> = q_producers
+        .iter()
+        .map(|(transform, _)| grids.grid_at(transform.translation.truncate()))
+        .collect();
+    let consumer_grids: Vec<Option<usize>> = q_consumers
+        .iter()
+        .map(|(transform, _)| grids.grid_at(transform.translation.truncate()))
+        .collect();
+
+    for ((_, producer), index) in q_producers.iter().zip(producer_grids.iter()) {
+        if let Some(index) = index {
+            let grid = &mut grids.grids[*index];
+            grid.producers += 1;
+            if producer.fueled {
+                grid.capacity += producer.max_output;
+            }
+        }
+    }
+
+    for ((_, consumer), index) in q_consumers.iter().zip(consumer_grids.iter()) {
+        if let Some(index) = index {
+            let grid = &mut grids.grids[*index];
+            grid.consumers += 1;
+            grid.demand += consumer.demand;
+        }
+    }
+
+    for grid in grids.grids.iter_mut() {
+        grid.satisfaction = if grid.demand <= 0.0 {
+            1.0
+        } else {
+            (grid.capacity / grid.demand).min(1.0)
+        };
+        grid.production = grid.capacity.min(grid.demand);
+    }
+
+    // Then hand out what each grid can cover
+    for ((_, mut producer), index) in q_producers.iter_mut().zip(producer_grids.iter()) {
+        producer.output = match index.map(|index| &grids.grids[index]) {
+            Some(grid) if producer.fueled && grid.capacity > 0.0 => {
+                producer.max_output * grid.production / grid.capacity
+            }
+            _ => 0.0,
+        };
+    }
+
+    for ((_, mut consumer), index) in q_consumers.iter_mut().zip(consumer_grids.iter()) {
+        consumer.satisfaction = index
+            .map(|index| grids.grids[index].satisfaction)
+            .unwrap_or(0.0);
+    }
+}
+
+fn draw_power_wires(mut gizmos: Gizmos, grids: Res<PowerGrids>) {
+    for (from, to) in grids.wires.iter() {
+        gizmos.line_2d(*from, *to, Color::srgba_u8(46, 34, 47, 200));
+    }
+}
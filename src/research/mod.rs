@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::buildings::helpers::BuildingKind;
+use crate::inventory::Stockpile;
+use crate::research::tree::{STARTING_BUILDINGS, TECHNOLOGIES, Technology, technology};
+use crate::stats::Material;
+
+pub mod tree;
+
+pub struct ResearchPlugin;
+
+impl Plugin for ResearchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<StartResearchMsg>()
+            .init_resource::<Research>()
+            .add_systems(Update, (start_research, run_research).chain());
+    }
+}
+
+#[derive(Message)]
+pub struct StartResearchMsg {
+    pub id: &'static str,
+}
+
+#[derive(Resource)]
+pub struct Research {
+    pub completed: HashSet<&'static str>,
+    pub current: Option<&'static str>,
+    // Share of the current research done, 0.0 to 1.0
+    pub progress: f32,
+    // How much of each cost the current research has already taken
+    paid: HashMap<Material, f32>,
+    unlocked: HashSet<BuildingKind>,
+}
+
+impl Default for Research {
+    fn default() -> Self {
+        Self {
+            completed: HashSet::new(),
+            current: None,
+            progress: 0.0,
+            paid: HashMap::new(),
+            unlocked: STARTING_BUILDINGS.iter().copied().collect(),
+        }
+    }
+}
+
+impl Research {
+    pub fn is_unlocked(&self, kind: BuildingKind) -> bool {
+        self.unlocked.contains(&kind)
+    }
+
+    pub fn is_available(&self, tech: &Technology) -> bool {
+        !self.completed.contains(tech.id)
+            && tech
+                .prerequisites
+                .iter()
+                .all(|prerequisite| self.completed.contains(prerequisite))
+    }
+
+    /// Technologies whose prerequisites are all done.
+    pub fn available(&self) -> impl Iterator<Item = &'static Technology> + '_ {
+        TECHNOLOGIES.iter().filter(|tech| self.is_available(tech))
+    }
+
+    pub fn paid(&self, material: Material) -> f32 {
+        self.paid.get(&material).copied().unwrap_or(0.0)
+    }
+
+    // Debug shortcut past the whole tree
+    pub fn unlock_all(&mut self) {
+        for tech in TECHNOLOGIES.iter() {
+            self.completed.insert(tech.id);
+            self.unlocked.extend(tech.unlocks.iter().copied());
+        }
+        self.current = None;
+        self.progress = 0.0;
+        self.paid.clear();
+    }
+
+    /// Picks a saved research back up with what had already been paid into it.
+    pub fn resume(
+        &mut self,
+        tech: &'static Technology,
+        progress: f32,
+        paid: impl IntoIterator<Item = (Material, f32)>,
+    ) {
+        self.current = Some(tech.id);
+        self.progress = progress.clamp(0.0, 1.0);
+        self.paid = paid.into_iter().collect();
+    }
+
+    pub fn complete(&mut self, tech: &'static Technology) {
+        self.completed.insert(tech.id);
+        self.unlocked.extend(tech.unlocks.iter().copied());
+        self.current = None;
+        self.progress = 0.0;
+        self.paid.clear();
+    }
+}
+
+fn start_research(mut msg_reader: MessageReader<StartResearchMsg>, mut research: ResMut<Research>) {
+    for msg in msg_reader.read() {
+        let Some(tech) = technology(msg.id) else {
+            continue;
+        };
+
+        if !research.is_available(tech) || research.current == Some(tech.id) {
+            continue;
+        }
+
+        // Switching drops whatever was paid into the old research
+        research.current = Some(tech.id);
+        research.progress = 0.0;
+        research.paid.clear();
+    }
+}
+
+fn run_research(time: Res<Time>, mut research: ResMut<Research>, mut stockpile: Stockpile) {
+    let Some(tech) = research.current.and_then(technology) else {
+        return;
+    };
+
+    let target = (research.progress + time.delta_secs() / tech.duration).min(1.0);
+    let mut progress = target;
+
+    for (material, amount) in tech.cost.iter() {
+        let owed = amount * target - research.paid(*material);
+
+        if owed > 0.0 {
+            let taken = stockpile.take(*material, owed);
+            *research.paid.entry(*material).or_insert(0.0) += taken;
+        }
+
+        // Progress only as far as the least paid input allows
+        progress = progress.min(research.paid(*material) / amount);
+    }
+
+    research.progress = research.progress.max(progress);
+
+    if research.progress >= 1.0 {
+        research.complete(tech);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::buildings::helpers::BuildingKind;
+use crate::fluids::Fluid;
+use crate::items::Item;
+use crate::stats::Material;
+
+pub struct Technology {
+    pub id: &'static str,
+    pub name: &'static str,
+    // Paid gradually while the research runs
+    pub cost: &'static [(Material, f32)],
+    // Seconds it takes with all inputs available
+    pub duration: f32,
+    pub prerequisites: &'static [&'static str],
+    pub unlocks: &'static [BuildingKind],
+}
+
+/// Buildings available before any research is done.
+pub const STARTING_BUILDINGS: &[BuildingKind] = &[
+    BuildingKind::Pipe,
+    BuildingKind::OilExtractor,
+    BuildingKind::SmallOilContainer,
+];
+
+pub const TECHNOLOGIES: &[Technology] = &[
+    Technology {
+        id: "fluid_storage",
+        name: "Fluid Storage",
+        cost: &[(Material::Fluid(Fluid::CrudeOil), 200.0)],
+        duration: 30.0,
+        prerequisites: &[],
+        unlocks: &[BuildingKind::MediumOilContainer],
+    },
+    Technology {
+        id: "flow_control",
+        name: "Flow Control",
+        cost: &[(Material::Fluid(Fluid::CrudeOil), 300.0)],
+        duration: 30.0,
+        prerequisites: &[],
+        unlocks: &[BuildingKind::CheckValve, BuildingKind::OverflowValve],
+    },
+    Technology {
+        id: "oil_processing",
+        name: "Oil Processing",
+        cost: &[(Material::Fluid(Fluid::CrudeOil), 500.0)],
+        duration: 60.0,
+        prerequisites: &["fluid_storage"],
+        unlocks: &[BuildingKind::OilRefinery],
+    },
+    Technology {
+        id: "power_generation",
+        name: "Power Generation",
+        cost: &[
+            (Material::Fluid(Fluid::CrudeOil), 200.0),
+            (Material::Fluid(Fluid::LightOil), 100.0),
+        ],
+        duration: 45.0,
+        prerequisites: &["oil_processing"],
+        unlocks: &[
+            BuildingKind::GasGenerator,
+            BuildingKind::PowerPole,
+            BuildingKind::Pump,
+        ],
+    },
+    Technology {
+        id: "bulk_storage",
+        name: "Bulk Storage",
+        cost: &[
+            (Material::Fluid(Fluid::LightOil), 300.0),
+            (Material::Fluid(Fluid::PetroleumGas), 200.0),
+        ],
+        duration: 60.0,
+        prerequisites: &["oil_processing"],
+        unlocks: &[BuildingKind::LargeOilContainer],
+    },
+    Technology {
+        id: "logistics",
+        name: "Logistics",
+        cost: &[
+            (Material::Item(Item::Plastic), 20.0),
+            (Material::Item(Item::Sulfur), 5.0),
+        ],
+        duration: 60.0,
+        prerequisites: &["oil_processing"],
+        unlocks: &[BuildingKind::Conveyor, BuildingKind::Inserter],
+    },
+];
+
+pub fn technology(id: &str) -> Option<&'static Technology> {
+    TECHNOLOGIES.iter().find(|tech| tech.id == id)
+}
@@ -0,0 +1,431 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::ecs::query::Allow;
+use bevy::prelude::*;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::buildings::helpers::{
+    BUILDING_Z, Building, BuildingKind, BuildingRotation, Footprint, grid_to_world, world_to_grid,
+};
+use crate::buildings::registry::BuildingRegistry;
+use crate::construction::{ConstructionJob, ConstructionQueue, Unbuilt, spawn_construction_site};
+use crate::fluids::{Fluid, FluidTank, FluidTanks};
+use crate::game::{GameState, MapSettings, MapSize};
+use crate::inventory::Inventory;
+use crate::items::{Item, ItemStorage};
+use crate::research::Research;
+use crate::research::tree::technology;
+use crate::stats::Material;
+use crate::tiles::terrain::TerrainMap;
+
+pub const SAVE_DIR: &str = "saves";
+const SAVE_EXTENSION: &str = "save";
+const SAVE_VERSION: u32 = 1;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingLoad>()
+            .init_resource::<SaveList>()
+            .add_systems(OnEnter(GameState::MainMenu), refresh_save_list)
+            .add_systems(OnEnter(GameState::InGame), apply_pending_load);
+    }
+}
+
+/// Names of the saves on disk, read once instead of on every menu frame.
+#[derive(Resource, Default)]
+pub struct SaveList(pub Vec<String>);
+
+/// A save read from disk, waiting for its map to finish loading.
+#[derive(Resource, Default)]
+pub struct PendingLoad(Option<SaveData>);
+
+struct SaveData {
+    seed: u64,
+    size: MapSize,
+    inventory: Vec<(Material, f32)>,
+    research: Vec<&'static str>,
+    // The research in progress and what has been paid into it
+    researching: Option<(&'static str, f32)>,
+    research_paid: Vec<(Material, f32)>,
+    // Deposits that have been drawn from, the rest come back from the seed
+    deposits: Vec<((i32, i32), f32)>,
+    buildings: Vec<SavedBuilding>,
+}
+
+struct SavedBuilding {
+    kind: BuildingKind,
+    tile: (i32, i32),
+    rotation: BuildingRotation,
+    // Still waiting on construction
+    planned: bool,
+    // Being torn down, the deconstruction starts over on load
+    deconstructing: bool,
+    tanks: Vec<(usize, Fluid, f32)>,
+    items: Vec<(Item, u32)>,
+}
+
+fn save_path(name: &str) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(format!("{name}.{SAVE_EXTENSION}"))
+}
+
+/// Names of every save on disk, sorted.
+pub fn list_saves() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(SAVE_DIR) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()?.to_str()? != SAVE_EXTENSION {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn refresh_save_list(mut save_list: ResMut<SaveList>) {
+    save_list.0 = list_saves();
+}
+
+// Names end up in file paths, so keep them to something every OS accepts
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "invalid save name '{name}', use letters, digits, '_' and '-'"
+        ));
+    }
+    Ok(())
+}
+
+/// Writes the current game to disk, returning how many buildings were saved.
+pub fn save_game(world: &mut World, name: &str) -> Result<usize, String> {
+    check_name(name)?;
+
+    let settings = world.resource::<MapSettings>();
+    let mut text = String::new();
+    let _ = writeln!(text, "version {SAVE_VERSION}");
+    let _ = writeln!(text, "seed {}", settings.seed);
+    let _ = writeln!(text, "size {}", settings.size.id());
+
+    let inventory = world.resource::<Inventory>();
+    for material in Material::ALL {
+        let amount = inventory.amount(material);
+        if amount > 0.0 {
+            let _ = writeln!(text, "inventory {} {amount}", material.id());
+        }
+    }
+
+    let research = world.resource::<Research>();
+    for id in &research.completed {
+        let _ = writeln!(text, "research {id}");
+    }
+    if let Some(id) = research.current {
+        let _ = writeln!(text, "researching {id} {}", research.progress);
+        for material in Material::ALL {
+            let paid = research.paid(material);
+            if paid > 0.0 {
+                let _ = writeln!(text, "research_paid {} {paid}", material.id());
+            }
+        }
+    }
+
+    let terrain = world.resource::<TerrainMap>();
+    for x in 0..terrain.size {
+        for y in 0..terrain.size {
+            if let Some(deposit) = terrain.deposit(x, y)
+                && deposit.amount < deposit.capacity
+            {
+                let (gx, gy) = terrain.tile_to_grid(x, y);
+                let _ = writeln!(text, "deposit {gx} {gy} {}", deposit.amount);
+            }
+        }
+    }
+
+    // Unbuilt and deconstructing buildings are disabled, ask for them too
+    let mut q_buildings = world.query_filtered::<(
+        &BuildingKind,
+        &Transform,
+        &BuildingRotation,
+        Has<Unbuilt>,
+        Has<Disabled>,
+        Option<&FluidTank>,
+        Option<&FluidTanks>,
+        Option<&ItemStorage>,
+    ), (With<Building>, Allow<Disabled>)>();
+
+    let mut count = 0;
+    for (kind, transform, rotation, planned, disabled, tank, tanks, storage) in
+        q_buildings.iter(world)
+    {
+        let (x, y) = world_to_grid(transform.translation);
+        // Finished buildings are only disabled while a deconstruction site stands in for them
+        let line = match (planned, disabled) {
+            (true, _) => "planned",
+            (false, true) => "deconstructing",
+            (false, false) => "building",
+        };
+        let _ = writeln!(text, "{line} {} {x} {y} {}", kind.id(), rotation.id());
+
+        let tanks = tank
+            .into_iter()
+            .chain(tanks.into_iter().flat_map(|t| t.0.iter()));
+        for (index, tank) in tanks.enumerate() {
+            if let Some(fluid) = tank.fluid
+                && !tank.is_empty()
+            {
+                let material = Material::Fluid(fluid);
+                let _ = writeln!(text, "tank {index} {} {}", material.id(), tank.amount);
+            }
+        }
+
+        for (item, held) in storage.iter().flat_map(|s| s.items.iter()) {
+            if *held > 0 {
+                let _ = writeln!(text, "item {} {held}", Material::Item(*item).id());
+            }
+        }
+
+        count += 1;
+    }
+
+    fs::create_dir_all(SAVE_DIR).map_err(|e| e.to_string())?;
+    fs::write(save_path(name), text).map_err(|e| e.to_string())?;
+    world.resource_mut::<SaveList>().0 = list_saves();
+    Ok(count)
+}
+
+/// Reads a save and starts loading its map, the buildings follow once it is ready.
+pub fn load_game(world: &mut World, name: &str) -> Result<(), String> {
+    check_name(name)?;
+
+    let text = fs::read_to_string(save_path(name)).map_err(|e| format!("{name}: {e}"))?;
+    let data = parse_save(&text)?;
+
+    let mut settings = world.resource_mut::<MapSettings>();
+    settings.seed = data.seed;
+    settings.size = data.size;
+
+    world.resource_mut::<PendingLoad>().0 = Some(data);
+    world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Loading);
+    Ok(())
+}
+
+fn parse_save(text: &str) -> Result<SaveData, String> {
+    let mut data = SaveData {
+        seed: 0,
+        size: MapSize::Small,
+        inventory: Vec::new(),
+        research: Vec::new(),
+        researching: None,
+        research_paid: Vec::new(),
+        deposits: Vec::new(),
+        buildings: Vec::new(),
+    };
+
+    for (number, line) in text.lines().enumerate() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let error = || format!("line {}: can't read '{line}'", number + 1);
+
+        match words.as_slice() {
+            [] => {}
+            ["version", version] => {
+                if version.parse::<u32>().ok() != Some(SAVE_VERSION) {
+                    return Err(format!("unsupported save version {version}"));
+                }
+            }
+            ["seed", seed] => data.seed = seed.parse().map_err(|_| error())?,
+            ["size", size] => data.size = MapSize::from_id(size).ok_or_else(error)?,
+            ["inventory", material, amount] => data.inventory.push((
+                Material::from_id(material).ok_or_else(error)?,
+                amount.parse().map_err(|_| error())?,
+            )),
+            ["research", id] => data.research.push(technology(id).ok_or_else(error)?.id),
+            ["researching", id, progress] => {
+                data.researching = Some((
+                    technology(id).ok_or_else(error)?.id,
+                    progress.parse().map_err(|_| error())?,
+                ));
+            }
+            ["research_paid", material, amount] => data.research_paid.push((
+                Material::from_id(material).ok_or_else(error)?,
+                amount.parse().map_err(|_| error())?,
+            )),
+            ["deposit", x, y, amount] => data.deposits.push((
+                (
+                    x.parse().map_err(|_| error())?,
+                    y.parse().map_err(|_| error())?,
+                ),
+                amount.parse().map_err(|_| error())?,
+            )),
+            [
+                state @ ("building" | "planned" | "deconstructing"),
+                kind,
+                x,
+                y,
+                rotation,
+            ] => {
+                data.buildings.push(SavedBuilding {
+                    kind: BuildingKind::from_id(kind).ok_or_else(error)?,
+                    tile: (
+                        x.parse().map_err(|_| error())?,
+                        y.parse().map_err(|_| error())?,
+                    ),
+                    rotation: BuildingRotation::from_id(rotation).ok_or_else(error)?,
+                    planned: *state == "planned",
+                    deconstructing: *state == "deconstructing",
+                    tanks: Vec::new(),
+                    items: Vec::new(),
+                });
+            }
+            // Contents belong to the building above them
+            ["tank", index, fluid, amount] => {
+                let Some(Material::Fluid(fluid)) = Material::from_id(fluid) else {
+                    return Err(error());
+                };
+                let building = data.buildings.last_mut().ok_or_else(error)?;
+                building.tanks.push((
+                    index.parse().map_err(|_| error())?,
+                    fluid,
+                    amount.parse().map_err(|_| error())?,
+                ));
+            }
+            ["item", item, count] => {
+                let Some(Material::Item(item)) = Material::from_id(item) else {
+                    return Err(error());
+                };
+                let building = data.buildings.last_mut().ok_or_else(error)?;
+                building
+                    .items
+                    .push((item, count.parse().map_err(|_| error())?));
+            }
+            _ => return Err(error()),
+        }
+    }
+
+    Ok(data)
+}
+
+/// Spawns a finished building on `tile` through the plugin that registered it.
+pub fn spawn_building(
+    world: &mut World,
+    kind: BuildingKind,
+    tile: (i32, i32),
+    rotation: BuildingRotation,
+) -> Option<Entity> {
+    let spawn = world.resource::<BuildingRegistry>().get(kind)?.spawn;
+    let position = grid_to_world(tile, kind.footprint()).extend(BUILDING_Z);
+
+    let entity = spawn(world, position, rotation);
+    world.flush();
+    Some(entity)
+}
+
+// Hands a loaded building to a deconstruction site, the way delete mode does
+fn deconstruct(world: &mut World, building: Entity) {
+    let entity = world.entity(building);
+    let (Some(kind), Some(sprite), Some(transform)) = (
+        entity.get::<BuildingKind>().copied(),
+        entity.get::<Sprite>().cloned(),
+        entity.get::<Transform>().copied(),
+    ) else {
+        return;
+    };
+    let size = entity.get::<Footprint>().map(|f| f.0).unwrap_or(1);
+
+    let ghost = {
+        let mut commands = world.commands();
+        commands.entity(building).insert(Disabled);
+        spawn_construction_site(
+            &mut commands,
+            building,
+            kind,
+            ConstructionJob::Deconstruct,
+            &sprite,
+            transform,
+            size,
+        )
+    };
+    world.flush();
+    world.resource_mut::<ConstructionQueue>().0.push(ghost);
+}
+
+fn apply_pending_load(world: &mut World) {
+    let Some(data) = world.resource_mut::<PendingLoad>().0.take() else {
+        return;
+    };
+
+    let mut inventory = world.resource_mut::<Inventory>();
+    for material in Material::ALL {
+        inventory.set(material, 0.0);
+    }
+    for (material, amount) in data.inventory {
+        inventory.set(material, amount);
+    }
+
+    let mut research = world.resource_mut::<Research>();
+    for tech in data.research.into_iter().filter_map(technology) {
+        research.complete(tech);
+    }
+    if let Some((tech, progress)) = data
+        .researching
+        .and_then(|(id, progress)| Some((technology(id)?, progress)))
+    {
+        research.resume(tech, progress, data.research_paid);
+    }
+
+    let mut terrain = world.resource_mut::<TerrainMap>();
+    for (tile, amount) in data.deposits {
+        if let Some((x, y)) = terrain.grid_to_tile(tile)
+            && let Some(deposit) = terrain.deposit_mut(x, y)
+        {
+            deposit.amount = amount.clamp(0.0, deposit.capacity);
+        }
+    }
+
+    for saved in data.buildings {
+        let Some(entity) = spawn_building(world, saved.kind, saved.tile, saved.rotation) else {
+            continue;
+        };
+
+        for (index, fluid, amount) in saved.tanks {
+            // Pipes hold a single tank of their own
+            let mut entity_mut = world.entity_mut(entity);
+            if let Some(mut tank) = entity_mut.get_mut::<FluidTank>() {
+                if index == 0 {
+                    let _ = tank.insert(fluid, amount);
+                }
+            } else if let Some(mut tanks) = entity_mut.get_mut::<FluidTanks>()
+                && let Some(tank) = tanks.0.get_mut(index)
+            {
+                let _ = tank.insert(fluid, amount);
+            }
+        }
+
+        if let Some(mut storage) = world.get_mut::<ItemStorage>(entity) {
+            for (item, count) in saved.items {
+                *storage.items.entry(item).or_insert(0) += count;
+            }
+        }
+
+        // Construction picks planned buildings up again from the start
+        if saved.planned {
+            world.entity_mut(entity).insert(Unbuilt);
+        }
+
+        if saved.deconstructing {
+            deconstruct(world, entity);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::buildings::helpers::BuildingKind;
+use crate::fluids::Fluid;
+use crate::items::Item;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProductionStats>()
+            .add_systems(First, tick_production_stats);
+    }
+}
+
+// Every window is split into this many samples
+pub const SAMPLES_PER_WINDOW: usize = 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Material {
+    Item(Item),
+    Fluid(Fluid),
+}
+
+impl Material {
+    pub const ALL: [Material; 5] = [
+        Material::Fluid(Fluid::CrudeOil),
+        Material::Fluid(Fluid::LightOil),
+        Material::Fluid(Fluid::PetroleumGas),
+        Material::Item(Item::Plastic),
+        Material::Item(Item::Sulfur),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Material::Item(item) => item.name(),
+            Material::Fluid(fluid) => fluid.name(),
+        }
+    }
+
+    /// Name used by console commands and save files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Material::Fluid(Fluid::CrudeOil) => "crude",
+            Material::Fluid(Fluid::LightOil) => "light_oil",
+            Material::Fluid(Fluid::PetroleumGas) => "petroleum_gas",
+            Material::Item(Item::Plastic) => "plastic",
+            Material::Item(Item::Sulfur) => "sulfur",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Material> {
+        Material::ALL
+            .into_iter()
+            .find(|material| material.id() == id)
+    }
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatWindow {
+    #[default]
+    OneMinute,
+    TenMinutes,
+    OneHour,
+}
+
+impl StatWindow {
+    pub const ALL: [StatWindow; 3] = [
+        StatWindow::OneMinute,
+        StatWindow::TenMinutes,
+        StatWindow::OneHour,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatWindow::OneMinute => "1 min",
+            StatWindow::TenMinutes => "10 min",
+            StatWindow::OneHour => "1 h",
+        }
+    }
+
+    pub fn minutes(&self) -> f32 {
+        match self {
+            StatWindow::OneMinute => 1.0,
+            StatWindow::TenMinutes => 10.0,
+            StatWindow::OneHour => 60.0,
+        }
+    }
+
+    pub fn sample_secs(&self) -> f32 {
+        self.minutes() * 60.0 / SAMPLES_PER_WINDOW as f32
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            StatWindow::OneMinute => 0,
+            StatWindow::TenMinutes => 1,
+            StatWindow::OneHour => 2,
+        }
+    }
+}
+
+/// Fixed number of samples, the oldest one is overwritten as time moves on.
+#[derive(Clone, Debug)]
+struct SampleRing {
+    samples: [f32; SAMPLES_PER_WINDOW],
+    // Index of the newest sample since the game started
+    head: u64,
+}
+
+impl Default for SampleRing {
+    fn default() -> Self {
+        Self {
+            samples: [0.0; SAMPLES_PER_WINDOW],
+            head: 0,
+        }
+    }
+}
+
+impl SampleRing {
+    fn advance_to(&mut self, sample: u64) {
+        // Zero out every sample we skipped, but never more than a full lap
+        let skipped = sample
+            .saturating_sub(self.head)
+            .min(SAMPLES_PER_WINDOW as u64);
+        for offset in 1..=skipped {
+            self.samples[((self.head + offset) % SAMPLES_PER_WINDOW as u64) as usize] = 0.0;
+        }
+        self.head = self.head.max(sample);
+    }
+
+    fn add(&mut self, sample: u64, amount: f32) {
+        self.advance_to(sample);
+        self.samples[(self.head % SAMPLES_PER_WINDOW as u64) as usize] += amount;
+    }
+
+    // Oldest first
+    fn ordered(&self, now: u64) -> [f32; SAMPLES_PER_WINDOW] {
+        let mut ordered = [0.0; SAMPLES_PER_WINDOW];
+
+        for (i, value) in ordered.iter_mut().enumerate() {
+            // Position of this slot counted back from `now`
+            let age = (SAMPLES_PER_WINDOW - 1 - i) as u64;
+            if age > now {
+                continue;
+            }
+
+            // Only samples still held in the ring count
+            let sample = now - age;
+            if sample + SAMPLES_PER_WINDOW as u64 <= self.head || sample > self.head {
+                continue;
+            }
+            *value = self.samples[(sample % SAMPLES_PER_WINDOW as u64) as usize];
+        }
+
+        ordered
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Series {
+    windows: [SampleRing; 3],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Flow {
+    Produced,
+    Consumed,
+}
+
+/// Production and consumption of every material, per building kind.
+#[derive(Resource, Default)]
+pub struct ProductionStats {
+    elapsed: f32,
+    series: HashMap<(Material, BuildingKind, Flow), Series>,
+}
+
+impl ProductionStats {
+    pub fn produced(&mut self, material: Material, building: BuildingKind, amount: f32) {
+        self.record(material, building, Flow::Produced, amount);
+    }
+
+    pub fn consumed(&mut self, material: Material, building: BuildingKind, amount: f32) {
+        self.record(material, building, Flow::Consumed, amount);
+    }
+
+    fn record(&mut self, material: Material, building: BuildingKind, flow: Flow, amount: f32) {
+        if amount <= 0.0 {
+            return;
+        }
+
+        let elapsed = self.elapsed;
+        let series = self.series.entry((material, building, flow)).or_default();
+        for window in StatWindow::ALL {
+            let sample = (elapsed / window.sample_secs()) as u64;
+            series.windows[window.index()].add(sample, amount);
+        }
+    }
+
+    fn now(&self, window: StatWindow) -> u64 {
+        (self.elapsed / window.sample_secs()) as u64
+    }
+
+    /// Every material that has been produced or consumed so far.
+    pub fn materials(&self) -> Vec<Material> {
+        let mut materials: Vec<Material> = Vec::new();
+        for (material, _, _) in self.series.keys() {
+            if !materials.contains(material) {
+                materials.push(*material);
+            }
+        }
+        materials.sort_by_key(|material| material.name());
+        materials
+    }
+
+    /// Building kinds that produced or consumed `material` so far.
+    pub fn buildings(&self, material: Material) -> Vec<BuildingKind> {
+        let mut buildings: Vec<BuildingKind> = Vec::new();
+        for (other, building, _) in self.series.keys() {
+            if *other == material && !buildings.contains(building) {
+                buildings.push(*building);
+            }
+        }
+        buildings.sort_by_key(|building| building.name());
+        buildings
+    }
+
+    fn samples(
+        &self,
+        material: Material,
+        building: Option<BuildingKind>,
+        flow: Flow,
+        window: StatWindow,
+    ) -> [f32; SAMPLES_PER_WINDOW] {
+        let now = self.now(window);
+        let mut total = [0.0; SAMPLES_PER_WINDOW];
+
+        for ((other, other_building, other_flow), series) in self.series.iter() {
+            if *other != material || *other_flow != flow {
+                continue;
+            }
+            if building.is_some_and(|building| building != *other_building) {
+                continue;
+            }
+
+            let samples = series.windows[window.index()].ordered(now);
+            for (sum, value) in total.iter_mut().zip(samples.iter()) {
+                *sum += value;
+            }
+        }
+
+        total
+    }
+
+    /// Amount produced in each sample of the window, oldest first.
+    pub fn produced_samples(
+        &self,
+        material: Material,
+        window: StatWindow,
+    ) -> [f32; SAMPLES_PER_WINDOW] {
+        self.samples(material, None, Flow::Produced, window)
+    }
+
+    /// Amount consumed in each sample of the window, oldest first.
+    pub fn consumed_samples(
+        &self,
+        material: Material,
+        window: StatWindow,
+    ) -> [f32; SAMPLES_PER_WINDOW] {
+        self.samples(material, None, Flow::Consumed, window)
+    }
+
+    /// Average produced per minute over the window, optionally by one building kind.
+    pub fn produced_per_minute(
+        &self,
+        material: Material,
+        building: Option<BuildingKind>,
+        window: StatWindow,
+    ) -> f32 {
+        let samples = self.samples(material, building, Flow::Produced, window);
+        samples.iter().sum::<f32>() / window.minutes()
+    }
+
+    /// Average consumed per minute over the window, optionally by one building kind.
+    pub fn consumed_per_minute(
+        &self,
+        material: Material,
+        building: Option<BuildingKind>,
+        window: StatWindow,
+    ) -> f32 {
+        let samples = self.samples(material, building, Flow::Consumed, window);
+        samples.iter().sum::<f32>() / window.minutes()
+    }
+}
+
+fn tick_production_stats(time: Res<Time>, mut stats: ResMut<ProductionStats>) {
+    stats.elapsed += time.delta_secs();
+}
@@ -1,40 +1,174 @@
 use bevy::prelude::*;
 use bevy_ecs_tilemap::prelude::*;
 
+use crate::game::{GameState, InWorld, MapSettings};
+use crate::tiles::terrain::{Deposit, Terrain, TerrainMap};
+
 pub mod picking;
+pub mod terrain;
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerrainMap>()
+            .add_systems(OnEnter(GameState::Loading), tiles_startup)
+            .add_systems(Update, dim_depleted_deposits.run_if(in_state(InWorld)));
+    }
+}
+
+// Depth of each layer within the map, all of them below buildings
+const GROUND_Z: f32 = 0.0;
+const RESOURCES_Z: f32 = 1.0;
+const DECORATION_Z: f32 = 2.0;
+
+// How faint an exhausted deposit is drawn, full deposits are opaque
+const DEPLETED_ALPHA: f32 = 0.2;
+
+/// Which part of the map a tilemap draws, each is a separate tilemap entity.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerrainLayer {
+    Ground,
+    Resources,
+    // Purely cosmetic, ignored by tile picking
+    Decoration,
+}
+
+fn tiles_startup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    map_settings: Res<MapSettings>,
+) {
+    // How big you want the map to be in tiles
+    let size = map_settings.size.tiles();
+    let terrain = TerrainMap::generate(map_settings.seed, size);
+
+    let positions = || (0..size).flat_map(move |x| (0..size).map(move |y| (x, y)));
+
+    // One ground layer per terrain, each drawn over the ones before it
+    for (order, kind) in Terrain::ALL.into_iter().enumerate() {
+        let tiles = positions()
+            .filter(|(x, y)| terrain.ground(*x, *y) >= kind)
+            .map(|(x, y)| {
+                let index = if kind.autotiled() {
+                    terrain.autotile_index(kind, x, y)
+                } else {
+                    0
+                };
+                (TilePos { x, y }, index)
+            });
+
+        spawn_layer(
+            &mut commands,
+            asset_server.load(kind.texture()),
+            size,
+            TerrainLayer::Ground,
+            GROUND_Z + order as f32 * 0.1,
+            tiles,
+        );
+    }
 
-pub fn tiles_startup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let texture_handle: Handle<Image> = asset_server.load("textures/grass.png");
+    // Deposit tiles come in a few variants so fields don't look stamped
+    let deposits = positions()
+        .filter(|(x, y)| {
+            terrain
+                .deposit(*x, *y)
+                .is_some_and(|deposit| deposit.kind == Deposit::Oil)
+        })
+        .map(|(x, y)| (TilePos { x, y }, (x * 7 + y * 3) % 4));
+    spawn_layer(
+        &mut commands,
+        asset_server.load(Deposit::Oil.texture()),
+        size,
+        TerrainLayer::Resources,
+        RESOURCES_Z,
+        deposits,
+    );
 
-    let map_size = TilemapSize { x: 32, y: 32 }; // How big you want the map to be in tiles
+    let decorations = positions().filter_map(|(x, y)| {
+        terrain
+            .decoration(x, y)
+            .map(|index| (TilePos { x, y }, index))
+    });
+    spawn_layer(
+        &mut commands,
+        asset_server.load("textures/terrain/decoration.png"),
+        size,
+        TerrainLayer::Decoration,
+        DECORATION_Z,
+        decorations,
+    );
+
+    commands.insert_resource(terrain);
+}
+
+// Deposit tiles fade as they are drawn down, the hover highlight keeps their alpha
+fn dim_depleted_deposits(
+    terrain: Res<TerrainMap>,
+    q_layers: Query<(&TerrainLayer, &TileStorage)>,
+    mut q_tiles: Query<&mut TileColor>,
+) {
+    if !terrain.is_changed() {
+        return;
+    }
+
+    for (layer, storage) in &q_layers {
+        if *layer != TerrainLayer::Resources {
+            continue;
+        }
+
+        for x in 0..terrain.size {
+            for y in 0..terrain.size {
+                let Some(deposit) = terrain.deposit(x, y) else {
+                    continue;
+                };
+                let Some(mut color) = storage
+                    .get(&TilePos { x, y })
+                    .and_then(|tile| q_tiles.get_mut(tile).ok())
+                else {
+                    continue;
+                };
+
+                let alpha = DEPLETED_ALPHA + (1.0 - DEPLETED_ALPHA) * deposit.remaining();
+                if color.0.alpha() != alpha {
+                    color.0.set_alpha(alpha);
+                }
+            }
+        }
+    }
+}
+
+// Spawns one tilemap covering the whole map, with tiles only where given
+fn spawn_layer(
+    commands: &mut Commands,
+    texture_handle: Handle<Image>,
+    size: u32,
+    layer: TerrainLayer,
+    z: f32,
+    tiles: impl Iterator<Item = (TilePos, u32)>,
+) {
+    let map_size = TilemapSize { x: size, y: size };
 
     // Create a tilemap entity a little early.
     // We want this entity early because we need to tell each tile which tilemap entity
     // it is associated with. This is done with the TilemapId component on each tile.
     // Eventually, we will insert the `TilemapBundle` bundle on the entity, which
     // will contain various necessary components, such as `TileStorage`.
-    let tilemap_entity = commands.spawn_empty().id();
+    let tilemap_entity = commands.spawn(layer).id();
 
-    // To begin creating the map we will need a `TileStorage` component.
-    // This component is a grid of tile entities and is used to help keep track of individual
-    // tiles in the world. If you have multiple layers of tiles you would have a tilemap entity
-    // per layer, each with their own `TileStorage` component.
+    // Each layer keeps its own `TileStorage`, empty where the layer has no tile
     let mut tile_storage = TileStorage::empty(map_size);
 
-    // Spawn the elements of the tilemap.
-    for x in 0..map_size.x {
-        for y in 0..map_size.y {
-            let tile_pos = TilePos { x, y };
-
-            let tile_entity = commands
-                .spawn(TileBundle {
-                    position: tile_pos,
-                    tilemap_id: TilemapId(tilemap_entity),
-                    ..Default::default()
-                })
-                .id();
-            tile_storage.set(&tile_pos, tile_entity);
-        }
+    for (tile_pos, index) in tiles {
+        let tile_entity = commands
+            .spawn(TileBundle {
+                position: tile_pos,
+                tilemap_id: TilemapId(tilemap_entity),
+                texture_index: TileTextureIndex(index),
+                ..Default::default()
+            })
+            .id();
+        tile_storage.set(&tile_pos, tile_entity);
     }
 
     let tile_size = TilemapTileSize { x: 32.0, y: 32.0 }; // How big the tiles are in pixels
@@ -49,6 +183,7 @@ pub fn tiles_startup(mut commands: Commands, asset_server: Res<AssetServer>) {
         texture: TilemapTexture::Single(texture_handle),
         tile_size,
         anchor: TilemapAnchor::Center,
+        transform: Transform::from_xyz(0.0, 0.0, z),
         ..Default::default()
     });
 }
@@ -1,4 +1,5 @@
-use crate::buildings::helpers::DeleteMode;
+use crate::bindings::routing::InputRouting;
+use crate::tiles::TerrainLayer;
 use bevy::picking::hover::PickingInteraction;
 use bevy::{
     picking::{
@@ -36,12 +37,14 @@ fn tile_picking(
         &TilemapAnchor,
         &GlobalTransform,
         &ViewVisibility,
+        &TerrainLayer,
     )>,
     q_tile: Query<&TileVisible>,
     mut output: MessageWriter<PointerHits>,
-    delete_mode: Res<DeleteMode>,
+    routing: Res<InputRouting>,
 ) {
-    if delete_mode.active {
+    // No hits leaves nothing hovered while the pointer is over a window
+    if routing.pointer_over_ui {
         return;
     }
 
@@ -75,9 +78,10 @@ fn tile_picking(
             continue;
         };
 
+        // Only the topmost layer with a tile under the cursor is hit, decorations are see-through
         let picks = q_tilemap
             .iter()
-            .filter(|(.., vis)| vis.get())
+            .filter(|(.., vis, layer)| vis.get() && **layer != TerrainLayer::Decoration)
             .filter_map(|(t_s, tgs, tty, t_store, tile_size, anchor, gt, ..)| {
                 // if blocked {
                 //     return None;
@@ -98,6 +102,8 @@ fn tile_picking(
                 let depth = -ortho.near - gt.translation().z;
                 Some((picked, HitData::new(cam_entity, depth, None, None)))
             })
+            .min_by(|(_, a), (_, b)| a.depth.total_cmp(&b.depth))
+            .into_iter()
             .collect();
         // f32 required by PointerHits
         #[allow(clippy::cast_precision_loss)]
@@ -110,15 +116,17 @@ fn highlight_hovered_tile(
     mut q_tiles: Query<(&mut TileColor, &PickingInteraction), Changed<PickingInteraction>>,
 ) {
     for (mut color, interaction) in q_tiles.iter_mut() {
+        // Alpha is left alone, depleted deposits use it to fade out
+        let alpha = color.0.alpha();
         match *interaction {
             PickingInteraction::Hovered => {
-                *color = TileColor(Color::srgba_u8(190, 220, 127, 255)); // #BEDC7F when hovered
+                *color = TileColor(Color::srgb_u8(190, 220, 127).with_alpha(alpha)); // #BEDC7F when hovered
             }
             PickingInteraction::Pressed => {
-                *color = TileColor(Color::srgba_u8(17, 35, 24, 255)); // #112318 when clicked
+                *color = TileColor(Color::srgb_u8(17, 35, 24).with_alpha(alpha)); // #112318 when clicked
             }
             PickingInteraction::None => {
-                *color = TileColor(Color::WHITE);
+                *color = TileColor(Color::WHITE.with_alpha(alpha));
             }
         }
     }
@@ -0,0 +1,245 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Tiles between the random points the terrain noise blends across
+const TERRAIN_CELL: u32 = 12;
+const DEPOSIT_CELL: u32 = 6;
+
+// Oil in the poorest and the richest deposit tiles
+const MIN_DEPOSIT_AMOUNT: f32 = 3000.0;
+const MAX_DEPOSIT_AMOUNT: f32 = 9000.0;
+
+// Share of a deposit's capacity it has to lose before the map redraws it
+const DEPOSIT_REDRAW_STEP: f32 = 0.01;
+
+// Share of grass tiles with a flower, pebble or bush on them
+const DECORATION_CHANCE: f64 = 0.04;
+
+/// Ground types, later ones are drawn over earlier ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Terrain {
+    Grass,
+    Dirt,
+    Sand,
+}
+
+impl Terrain {
+    pub const ALL: [Terrain; 3] = [Terrain::Grass, Terrain::Dirt, Terrain::Sand];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Terrain::Grass => "Grass",
+            Terrain::Dirt => "Dirt",
+            Terrain::Sand => "Sand",
+        }
+    }
+
+    pub fn texture(&self) -> &'static str {
+        match self {
+            Terrain::Grass => "textures/grass.png",
+            Terrain::Dirt => "textures/terrain/dirt.png",
+            Terrain::Sand => "textures/terrain/sand.png",
+        }
+    }
+
+    // Grass is the bottom layer and covers every tile, so it has no edges
+    pub fn autotiled(&self) -> bool {
+        *self != Terrain::Grass
+    }
+
+    /// Pollution soaked up per second by one tile of this ground.
+    pub fn pollution_absorption(&self) -> f32 {
+        match self {
+            Terrain::Grass => 0.3,
+            Terrain::Dirt => 0.1,
+            Terrain::Sand => 0.02,
+        }
+    }
+}
+
+/// Resources lying under the ground, shown on their own tilemap layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Deposit {
+    Oil,
+}
+
+impl Deposit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Deposit::Oil => "Oil",
+        }
+    }
+
+    pub fn texture(&self) -> &'static str {
+        match self {
+            Deposit::Oil => "textures/terrain/oil.png",
+        }
+    }
+}
+
+/// A deposit on one tile, extractors draw it down until it runs dry.
+#[derive(Clone, Copy, Debug)]
+pub struct DepositTile {
+    pub kind: Deposit,
+    pub amount: f32,
+    pub capacity: f32,
+}
+
+impl DepositTile {
+    /// Share of the deposit still in the ground, from 1 down to 0.
+    pub fn remaining(&self) -> f32 {
+        if self.capacity > 0.0 {
+            (self.amount / self.capacity).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Which visible step the deposit is on, the map only redraws when this changes.
+    pub fn redraw_step(&self) -> u32 {
+        // Reaching zero is always its own step, so a deposit running dry always shows
+        (self.remaining() / DEPOSIT_REDRAW_STEP).ceil() as u32
+    }
+}
+
+/// What the map is made of, generated from `MapSettings::seed`.
+///
+/// Indexed by `TilePos`, which starts at the bottom left corner of the map.
+#[derive(Resource, Default)]
+pub struct TerrainMap {
+    pub size: u32,
+    ground: Vec<Terrain>,
+    deposits: Vec<Option<DepositTile>>,
+    // Atlas index of the decoration on each tile, if any
+    decorations: Vec<Option<u32>>,
+}
+
+impl TerrainMap {
+    pub fn generate(seed: u64, size: u32) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let height = value_noise(&mut rng, size, TERRAIN_CELL);
+        let richness = value_noise(&mut rng, size, DEPOSIT_CELL);
+
+        let ground: Vec<Terrain> = height
+            .iter()
+            .map(|height| match height {
+                h if *h > 0.72 => Terrain::Sand,
+                h if *h < 0.3 => Terrain::Dirt,
+                _ => Terrain::Grass,
+            })
+            .collect();
+
+        // Oil pools under grass and dirt, never under sand, richer towards the middle of a field
+        let deposits = ground
+            .iter()
+            .zip(&richness)
+            .map(|(terrain, richness)| {
+                (*terrain != Terrain::Sand && *richness > 0.78).then(|| {
+                    let capacity = MIN_DEPOSIT_AMOUNT
+                        + (MAX_DEPOSIT_AMOUNT - MIN_DEPOSIT_AMOUNT) * (richness - 0.78) / 0.22;
+                    DepositTile {
+                        kind: Deposit::Oil,
+                        amount: capacity,
+                        capacity,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let decorations = ground
+            .iter()
+            .zip(&deposits)
+            .map(|(terrain, deposit)| {
+                let bare = *terrain == Terrain::Grass && deposit.is_none();
+                (bare && rng.random_bool(DECORATION_CHANCE)).then(|| rng.random_range(0..4))
+            })
+            .collect();
+
+        Self {
+            size,
+            ground,
+            deposits,
+            decorations,
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y * self.size + x) as usize
+    }
+
+    pub fn ground(&self, x: u32, y: u32) -> Terrain {
+        self.ground[self.index(x, y)]
+    }
+
+    pub fn deposit(&self, x: u32, y: u32) -> Option<&DepositTile> {
+        self.deposits[self.index(x, y)].as_ref()
+    }
+
+    pub fn deposit_mut(&mut self, x: u32, y: u32) -> Option<&mut DepositTile> {
+        let index = self.index(x, y);
+        self.deposits[index].as_mut()
+    }
+
+    /// The `TilePos` of a grid tile, the map is centered on the origin.
+    pub fn grid_to_tile(&self, tile: (i32, i32)) -> Option<(u32, u32)> {
+        let half = self.size as i32 / 2;
+        let (x, y) = (tile.0 + half, tile.1 + half);
+        (x >= 0 && y >= 0 && x < self.size as i32 && y < self.size as i32)
+            .then_some((x as u32, y as u32))
+    }
+
+    pub fn tile_to_grid(&self, x: u32, y: u32) -> (i32, i32) {
+        let half = self.size as i32 / 2;
+        (x as i32 - half, y as i32 - half)
+    }
+
+    pub fn decoration(&self, x: u32, y: u32) -> Option<u32> {
+        self.decorations[self.index(x, y)]
+    }
+
+    /// Atlas index of a terrain layer tile, picked by which of the four
+    /// neighbours the layer also covers (north 1, east 2, south 4, west 8).
+    ///
+    /// A layer covers its own terrain and every terrain drawn above it, so
+    /// edges only show where the ground changes to something lower.
+    pub fn autotile_index(&self, terrain: Terrain, x: u32, y: u32) -> u32 {
+        let neighbours = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+        neighbours
+            .iter()
+            .enumerate()
+            .filter(|(_, (dx, dy))| {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                let outside = nx < 0 || ny < 0 || nx >= self.size as i32 || ny >= self.size as i32;
+                // The map border is not an edge
+                outside || self.ground(nx as u32, ny as u32) >= terrain
+            })
+            .map(|(bit, _)| 1 << bit)
+            .sum()
+    }
+}
+
+// Random values on a coarse lattice, smoothly blended between, one per tile
+fn value_noise(rng: &mut StdRng, size: u32, cell: u32) -> Vec<f32> {
+    let lattice = size / cell + 2;
+    let points: Vec<f32> = (0..lattice * lattice).map(|_| rng.random()).collect();
+    let point = |x: u32, y: u32| points[(y * lattice + x) as usize];
+
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+
+    let mut values = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let (cx, cy) = (x / cell, y / cell);
+            let tx = smooth((x % cell) as f32 / cell as f32);
+            let ty = smooth((y % cell) as f32 / cell as f32);
+
+            let bottom = point(cx, cy) + (point(cx + 1, cy) - point(cx, cy)) * tx;
+            let top = point(cx, cy + 1) + (point(cx + 1, cy + 1) - point(cx, cy + 1)) * tx;
+            values.push(bottom + (top - bottom) * ty);
+        }
+    }
+    values
+}
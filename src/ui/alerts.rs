@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::alerts::BuildingAlert;
+use crate::buildings::helpers::{BuildingKind, world_to_grid};
+use crate::game::InWorld;
+
+pub struct AlertsEguiPlugin;
+
+impl Plugin for AlertsEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            alerts_egui_window.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+fn alerts_egui_window(
+    mut contexts: EguiContexts,
+    q_buildings: Query<(&BuildingKind, &BuildingAlert, &Transform), Without<Camera2d>>,
+    mut q_camera: Single<&mut Transform, With<Camera2d>>,
+) -> Result {
+    let mut alerts: Vec<_> = q_buildings
+        .iter()
+        .filter_map(|(kind, alert, transform)| {
+            alert
+                .active()
+                .map(|alert| (alert, *kind, transform.translation))
+        })
+        .collect();
+
+    // Keep the list from jumping around between frames
+    alerts.sort_by_key(|(alert, kind, position)| {
+        (alert.atlas_index(), kind.name(), world_to_grid(*position))
+    });
+
+    egui::Window::new(format!("Alerts ({})", alerts.len()))
+        .id(egui::Id::new("alerts"))
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            if alerts.is_empty() {
+                ui.label("Everything is running");
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for (alert, kind, position) in alerts.iter() {
+                        let [r, g, b] = alert.ui_color();
+                        let (x, y) = world_to_grid(*position);
+
+                        ui.horizontal(|ui| {
+                            ui.colored_label(egui::Color32::from_rgb(r, g, b), alert.name());
+                            ui.label(format!("{} at ({}, {})", kind.name(), x, y));
+
+                            if ui.button("Jump").clicked() {
+                                q_camera.translation.x = position.x;
+                                q_camera.translation.y = position.y;
+                            }
+                        });
+                    }
+                });
+        });
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::console::{Console, LineKind};
+use crate::game::InWorld;
+
+const INPUT_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 150, 150);
+const ERROR_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 70, 60);
+
+pub struct ConsoleEguiPlugin;
+
+impl Plugin for ConsoleEguiPlugin {
+    fn build(&self, app: &mut App) {
+        // Developer tools only, release builds don't get the console
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        app.add_systems(
+            EguiPrimaryContextPass,
+            console_egui_window.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+// Puts the text cursor after whatever history or completion just wrote
+fn move_cursor_to_end(ctx: &egui::Context, id: egui::Id, text: &str) {
+    if let Some(mut state) = egui::TextEdit::load_state(ctx, id) {
+        let end = egui::text::CCursor::new(text.chars().count());
+        state
+            .cursor
+            .set_char_range(Some(egui::text::CCursorRange::one(end)));
+        state.store(ctx, id);
+    }
+}
+
+fn console_egui_window(
+    mut contexts: EguiContexts,
+    mut console: ResMut<Console>,
+    mut was_open: Local<bool>,
+) -> Result {
+    let just_opened = console.open && !*was_open;
+    *was_open = console.open;
+    if !console.open {
+        return Ok(());
+    }
+
+    let ctx = contexts.ctx_mut()?;
+    let input_id = egui::Id::new("console_input");
+    let mut open = true;
+
+    egui::Window::new("Console")
+        .open(&mut open)
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(260.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for (kind, text) in &console.output {
+                        let color = match kind {
+                            LineKind::Input => INPUT_COLOR,
+                            LineKind::Output => ui.visuals().text_color(),
+                            LineKind::Error => ERROR_COLOR,
+                        };
+                        ui.label(egui::RichText::new(text).monospace().color(color));
+                    }
+                });
+
+            ui.separator();
+
+            // Tab completes rather than moving focus to the next widget
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                    .id(input_id)
+                    .font(egui::TextStyle::Monospace)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("help")
+                    .lock_focus(true),
+            );
+
+            if just_opened {
+                response.request_focus();
+            }
+
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                console.submit();
+                response.request_focus();
+            }
+
+            if response.has_focus() {
+                let (tab, up, down) = ui.input(|i| {
+                    (
+                        i.key_pressed(egui::Key::Tab),
+                        i.key_pressed(egui::Key::ArrowUp),
+                        i.key_pressed(egui::Key::ArrowDown),
+                    )
+                });
+
+                if tab {
+                    console.autocomplete();
+                } else if up {
+                    console.history_previous();
+                } else if down {
+                    console.history_next();
+                }
+
+                if tab || up || down {
+                    move_cursor_to_end(ui.ctx(), input_id, &console.input);
+                }
+            }
+        });
+
+    if !open {
+        console.open = false;
+    }
+
+    Ok(())
+}
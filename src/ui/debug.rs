@@ -1,194 +1,81 @@
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
 
-use crate::buildings::helpers::{DeleteMode, delete_clicked_building};
-
-use crate::buildings::{oil_container, oil_extractor, oil_refinery, pipe};
+use crate::buildings::helpers::CancelPlacementMsg;
+use crate::buildings::placement::StartPlacementMsg;
+use crate::buildings::registry::BuildingRegistry;
+use crate::game::InWorld;
+use crate::inventory::Inventory;
+use crate::research::Research;
+use crate::stats::Material;
+use crate::ui::toolbar::building_icon;
 
 pub struct DebugEguiPlugin;
 
 impl Plugin for DebugEguiPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<DeleteMode>()
-            .add_systems(Startup, setup_building_images)
-            .add_systems(Update, delete_clicked_building)
-            .add_systems(EguiPrimaryContextPass, debug_egui_menu);
+        // Developer tools only, release builds don't get the panel
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        app.add_systems(
+            EguiPrimaryContextPass,
+            debug_egui_menu.run_if(in_state(InWorld)),
+        );
     }
 }
 
-#[derive(Resource)]
-struct BuildingImages {
-    pipe: Handle<Image>,
-    oil_extractor: Handle<Image>,
-    oil_containers: Handle<Image>,
-    oil_refinery: Handle<Image>,
-}
-
-fn setup_building_images(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(BuildingImages {
-        pipe: asset_server.load("textures/pipe.png"),
-        oil_extractor: asset_server.load("textures/oil_extractor.png"),
-        oil_containers: asset_server.load("textures/oil_container.png"),
-        oil_refinery: asset_server.load("textures/oil_refinery.png"),
-    });
-}
-
 fn debug_egui_menu(
+    mut commands: Commands,
     mut contexts: EguiContexts,
-    building_images: Res<BuildingImages>,
-    time: Res<Time>,
-    mut delete_mode: ResMut<DeleteMode>,
-    mut spawn_pipe_writer: MessageWriter<pipe::SpawnPipeMsg>,
-    mut spawn_oil_extractor_writer: MessageWriter<oil_extractor::SpawnOilExtractorMsg>,
-    mut spawn_small_oil_container_writer: MessageWriter<oil_container::SpawnSmallOilContainerMsg>,
-    mut spawn_medium_oil_container_writer: MessageWriter<oil_container::SpawnMediumOilContainerMsg>,
-    mut spawn_large_oil_container_writer: MessageWriter<oil_container::SpawnLargeOilContainerMsg>,
-    mut spawn_oil_refinery_writer: MessageWriter<oil_refinery::SpawnOilRefineryMsg>,
+    asset_server: Res<AssetServer>,
+    registry: Res<BuildingRegistry>,
+    time: Res<Time<Real>>,
+    mut research: ResMut<Research>,
+    mut inventory: ResMut<Inventory>,
 ) -> Result {
-    let fps = 10.0;
-
-    let pipe_tid = contexts.add_image(bevy_egui::EguiTextureHandle::Strong(
-        building_images.pipe.clone(),
-    ));
-
-    let oil_extractor_tid = contexts.add_image(bevy_egui::EguiTextureHandle::Strong(
-        building_images.oil_extractor.clone(),
-    ));
-
-    let oil_containers_tid = contexts.add_image(bevy_egui::EguiTextureHandle::Strong(
-        building_images.oil_containers.clone(),
-    ));
-
-    let oil_refinery_tid = contexts.add_image(bevy_egui::EguiTextureHandle::Strong(
-        building_images.oil_refinery.clone(),
-    ));
-
-    egui::Window::new("DEBUG").show(contexts.ctx_mut()?, |ui| {
-        ui.label("Tools");
-        ui.checkbox(&mut delete_mode.active, "Delete Mode");
-        ui.label("Buildings");
-
-        // Pipe
-        ui.collapsing("Pipe", |ui| {
-            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(0.25, 1.0));
-
-            let image = egui::Image::new(egui::load::SizedTexture::new(
-                pipe_tid,
-                egui::vec2(32.0, 32.0),
-            ))
-            .uv(uv);
-
-            ui.add(image);
-
-            if ui.button("Spawn").clicked() {
-                spawn_pipe_writer.write(pipe::SpawnPipeMsg);
+    let icons: Vec<_> = registry
+        .buildings
+        .iter()
+        .map(|info| building_icon(&mut contexts, &asset_server, info, time.elapsed_secs()))
+        .collect();
+
+    let mut picked = None;
+
+    egui::Window::new("DEBUG")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.label("Cheats");
+            if ui.button("Unlock all research").clicked() {
+                research.unlock_all();
             }
-        });
-
-        // Oil extractor
-        ui.collapsing("Oil Extractor", |ui| {
-            let num_frames = 5;
-
-            let frame_index = ((time.elapsed_secs() * fps) as usize) % num_frames;
-
-            let u_min = (frame_index as f32 * 32.0) / 160.0;
-            let u_max = ((frame_index + 1) as f32 * 32.0) / 160.0;
-
-            let uv = egui::Rect::from_min_max(egui::pos2(u_min, 0.0), egui::pos2(u_max, 1.0));
-
-            let image = egui::Image::new(egui::load::SizedTexture::new(
-                oil_extractor_tid,
-                egui::vec2(32.0, 32.0),
-            ))
-            .uv(uv);
-
-            ui.add(image);
-
-            if ui.button("Spawn").clicked() {
-                spawn_oil_extractor_writer.write(oil_extractor::SpawnOilExtractorMsg);
-            }
-        });
-
-        // Oil containers
-        ui.collapsing("Oil Containers", |ui| {
-            // Small container
-            ui.label("Small");
-            let uv_small = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(0.333, 1.0));
-
-            let image_small = egui::Image::new(egui::load::SizedTexture::new(
-                oil_containers_tid,
-                egui::vec2(32.0, 32.0),
-            ))
-            .uv(uv_small);
-
-            ui.add(image_small);
-
-            if ui.button("Spawn").clicked() {
-                spawn_small_oil_container_writer.write(oil_container::SpawnSmallOilContainerMsg);
-            }
-
-            ui.separator();
-
-            // Medium container
-            ui.label("Medium");
-            let uv_medium =
-                egui::Rect::from_min_max(egui::pos2(0.333, 0.0), egui::pos2(0.666, 1.0));
-
-            let image_medium = egui::Image::new(egui::load::SizedTexture::new(
-                oil_containers_tid,
-                egui::vec2(32.0, 32.0),
-            ))
-            .uv(uv_medium);
-
-            ui.add(image_medium);
-
-            if ui.button("Spawn").clicked() {
-                spawn_medium_oil_container_writer.write(oil_container::SpawnMediumOilContainerMsg);
+            if ui.button("Add 1000 of everything").clicked() {
+                for material in Material::ALL {
+                    inventory.add(material, 1000.0);
+                }
             }
 
             ui.separator();
+            ui.label("Buildings, locked or not");
 
-            // Large container
-            ui.label("Large");
-            let uv_large = egui::Rect::from_min_max(egui::pos2(0.666, 0.0), egui::pos2(1.0, 1.0));
-
-            let image_large = egui::Image::new(egui::load::SizedTexture::new(
-                oil_containers_tid,
-                egui::vec2(32.0, 32.0),
-            ))
-            .uv(uv_large);
-
-            ui.add(image_large);
+            for (info, icon) in registry.buildings.iter().zip(icons) {
+                ui.collapsing(info.kind.name(), |ui| {
+                    ui.add(icon);
 
-            if ui.button("Spawn").clicked() {
-                spawn_large_oil_container_writer.write(oil_container::SpawnLargeOilContainerMsg);
+                    if ui.button("Spawn").clicked() {
+                        picked = Some(info.kind);
+                    }
+                });
             }
         });
 
-        // Oil refinery
-        ui.collapsing("Oil Refinery", |ui| {
-            let num_frames = 5;
-
-            let frame_index = ((time.elapsed_secs() * fps) as usize) % num_frames;
-
-            let u_min = (frame_index as f32 * 64.0) / 320.0;
-            let u_max = ((frame_index + 1) as f32 * 64.0) / 320.0;
-
-            let uv = egui::Rect::from_min_max(egui::pos2(u_min, 0.0), egui::pos2(u_max, 1.0));
-
-            let image = egui::Image::new(egui::load::SizedTexture::new(
-                oil_refinery_tid,
-                egui::vec2(64.0, 64.0),
-            ))
-            .uv(uv);
-
-            ui.add(image);
-
-            if ui.button("Spawn").clicked() {
-                spawn_oil_refinery_writer.write(oil_refinery::SpawnOilRefineryMsg);
-            }
+    if let Some(kind) = picked {
+        commands.queue(move |world: &mut World| {
+            world.write_message(CancelPlacementMsg);
+            world.write_message(StartPlacementMsg(kind));
         });
-    });
+    }
 
     Ok(())
 }
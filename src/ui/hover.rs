@@ -0,0 +1,120 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::ecs::query::Allow;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::buildings::helpers::{
+    Building, BuildingKind, BuildingRotation, BuildingStatus, footprint_tiles, world_to_grid,
+};
+use crate::construction::Unbuilt;
+use crate::game::InWorld;
+use crate::pollution::Pollution;
+use crate::tiles::terrain::TerrainMap;
+
+pub struct HoverTooltipEguiPlugin;
+
+impl Plugin for HoverTooltipEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            hover_tooltip_egui.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+// Keeps the tooltip clear of the cursor and the tile highlight under it
+const CURSOR_OFFSET: egui::Vec2 = egui::vec2(16.0, 16.0);
+
+fn hover_tooltip_egui(
+    mut contexts: EguiContexts,
+    window: Single<&Window, With<PrimaryWindow>>,
+    q_camera: Single<(&Camera, &GlobalTransform)>,
+    terrain: Res<TerrainMap>,
+    pollution: Res<Pollution>,
+    // Planned and deconstructing buildings are disabled but still worth describing
+    q_buildings: Query<
+        (
+            &BuildingKind,
+            &Transform,
+            &BuildingRotation,
+            Option<&BuildingStatus>,
+            Has<Unbuilt>,
+            Has<Disabled>,
+        ),
+        (With<Building>, Allow<Disabled>),
+    >,
+) -> Result {
+    let ctx = contexts.ctx_mut()?;
+
+    // Menus and windows under the pointer get it to themselves
+    if ctx.is_pointer_over_area() {
+        return Ok(());
+    }
+    let Some(pointer) = ctx.pointer_hover_pos() else {
+        return Ok(());
+    };
+
+    let (camera, camera_transform) = q_camera.into_inner();
+    let Some(world_pos) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    else {
+        return Ok(());
+    };
+
+    let grid = world_to_grid(world_pos.extend(0.0));
+    let Some((x, y)) = terrain.grid_to_tile(grid) else {
+        return Ok(());
+    };
+
+    let building = q_buildings.iter().find(|(kind, transform, ..)| {
+        footprint_tiles(transform.translation, kind.footprint()).contains(&grid)
+    });
+
+    egui::Area::new(egui::Id::new("hover_tooltip"))
+        .fixed_pos(pointer + CURSOR_OFFSET)
+        .order(egui::Order::Tooltip)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                if let Some((kind, _, rotation, status, unbuilt, disabled)) = building {
+                    ui.strong(kind.name());
+                    ui.label(format!("Facing {}", rotation.name()));
+
+                    let state = if unbuilt {
+                        "Planned"
+                    } else if disabled {
+                        "Deconstructing"
+                    } else {
+                        status.map(|status| status.name()).unwrap_or("Built")
+                    };
+                    ui.label(state);
+                    ui.separator();
+                }
+
+                ui.label(format!(
+                    "Tile ({}, {}), {}",
+                    grid.0,
+                    grid.1,
+                    terrain.ground(x, y).name()
+                ));
+
+                if let Some(deposit) = terrain.deposit(x, y) {
+                    ui.label(format!(
+                        "{}: {:.0} / {:.0}",
+                        deposit.kind.name(),
+                        deposit.amount,
+                        deposit.capacity
+                    ));
+                }
+
+                let polluted = pollution.at(x, y);
+                if polluted >= 0.1 {
+                    ui.label(format!("Pollution: {polluted:.1}"));
+                }
+            });
+        });
+
+    Ok(())
+}
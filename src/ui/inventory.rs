@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::game::InWorld;
+use crate::inventory::{PlacementRefusal, Stockpile};
+use crate::stats::Material;
+
+pub struct InventoryEguiPlugin;
+
+impl Plugin for InventoryEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            (inventory_egui_window, placement_refusal_egui).run_if(in_state(InWorld)),
+        );
+    }
+}
+
+fn inventory_egui_window(mut contexts: EguiContexts, stockpile: Stockpile) -> Result {
+    egui::Window::new("Inventory")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            egui::Grid::new("inventory_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Material");
+                    ui.label("Inventory");
+                    ui.label("Storage");
+                    ui.end_row();
+
+                    for material in Material::ALL {
+                        ui.label(material.name());
+                        ui.label(format!("{:.0}", stockpile.inventory.amount(material)));
+                        ui.label(format!("{:.0}", stockpile.stored(material)));
+                        ui.end_row();
+                    }
+                });
+
+            ui.small("Buildings are paid from the inventory first, deleting one refunds it there");
+        });
+
+    Ok(())
+}
+
+fn placement_refusal_egui(mut contexts: EguiContexts, refusal: Res<PlacementRefusal>) -> Result {
+    let Some(reason) = &refusal.reason else {
+        return Ok(());
+    };
+
+    egui::Area::new(egui::Id::new("placement_refusal"))
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 16.0))
+        .interactable(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.colored_label(egui::Color32::from_rgb(230, 70, 60), reason);
+            });
+        });
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::bindings::{Action, KeyBindings, Rebinding};
+use crate::game::{GameState, MapSettings, MapSize, Settings};
+use crate::save::{SaveList, load_game};
+
+pub struct MenuEguiPlugin;
+
+impl Plugin for MenuEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            (
+                apply_ui_scale,
+                main_menu_egui.run_if(in_state(GameState::MainMenu)),
+                loading_egui.run_if(in_state(GameState::Loading)),
+                pause_menu_egui.run_if(in_state(GameState::Paused)),
+            ),
+        );
+    }
+}
+
+fn settings_ui(ui: &mut egui::Ui, settings: &mut Settings) {
+    ui.add(egui::Slider::new(&mut settings.pan_speed, 0.25..=3.0).text("Camera speed"));
+    ui.checkbox(&mut settings.edge_scroll, "Edge scrolling");
+    ui.add(egui::Slider::new(&mut settings.ui_scale, 0.75..=2.0).text("UI scale"));
+}
+
+const CONFLICT_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 70, 60);
+
+fn controls_ui(ui: &mut egui::Ui, bindings: &mut KeyBindings, rebinding: &mut Rebinding) {
+    egui::Grid::new("controls_grid")
+        .striped(true)
+        .show(ui, |ui| {
+            for action in Action::ALL {
+                ui.label(action.name());
+
+                let label = if rebinding.0 == Some(action) {
+                    "Press a key (Esc cancels)".to_string()
+                } else {
+                    bindings.label(action)
+                };
+                if ui.button(label).clicked() {
+                    rebinding.0 = Some(action);
+                }
+
+                // Shared keys are allowed, but both actions fire together
+                let conflicts = bindings.conflicts(action);
+                if conflicts.is_empty() {
+                    ui.label("");
+                } else {
+                    let names: Vec<String> = conflicts.iter().map(|other| other.name()).collect();
+                    ui.colored_label(
+                        CONFLICT_COLOR,
+                        format!("Conflicts with {}", names.join(", ")),
+                    );
+                }
+                ui.end_row();
+            }
+        });
+
+    if ui.button("Reset to defaults").clicked() {
+        *bindings = KeyBindings::default();
+        bindings.save();
+        rebinding.0 = None;
+    }
+}
+
+fn apply_ui_scale(mut contexts: EguiContexts, settings: Res<Settings>) -> Result {
+    if settings.is_changed() {
+        contexts.ctx_mut()?.set_zoom_factor(settings.ui_scale);
+    }
+
+    Ok(())
+}
+
+fn main_menu_egui(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut map_settings: ResMut<MapSettings>,
+    mut settings: ResMut<Settings>,
+    mut bindings: ResMut<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    save_list: Res<SaveList>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: MessageWriter<AppExit>,
+) -> Result {
+    egui::Window::new("Machina 2D")
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .collapsible(false)
+        .resizable(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.heading("New Game");
+
+            egui::Grid::new("new_game_grid").show(ui, |ui| {
+                ui.label("Seed");
+                ui.add(egui::DragValue::new(&mut map_settings.seed));
+                ui.end_row();
+
+                ui.label("Map size");
+                egui::ComboBox::from_id_salt("map_size")
+                    .selected_text(map_settings.size.name())
+                    .show_ui(ui, |ui| {
+                        for size in MapSize::ALL {
+                            ui.selectable_value(&mut map_settings.size, size, size.name());
+                        }
+                    });
+                ui.end_row();
+            });
+
+            if ui.button("Start").clicked() {
+                next_state.set(GameState::Loading);
+            }
+
+            ui.separator();
+            ui.heading("Load Game");
+            if save_list.0.is_empty() {
+                ui.label("No saved games yet");
+            }
+            for name in save_list.0.iter().cloned() {
+                if ui.button(&name).clicked() {
+                    commands.queue(move |world: &mut World| {
+                        if let Err(error) = load_game(world, &name) {
+                            warn!("Could not load {name}: {error}");
+                        }
+                    });
+                }
+            }
+
+            ui.separator();
+            ui.collapsing("Settings", |ui| {
+                settings_ui(ui, &mut settings);
+            });
+            ui.collapsing("Controls", |ui| {
+                controls_ui(ui, &mut bindings, &mut rebinding);
+            });
+
+            ui.separator();
+            if ui.button("Quit").clicked() {
+                exit.write(AppExit::Success);
+            }
+        });
+
+    Ok(())
+}
+
+fn loading_egui(mut contexts: EguiContexts) -> Result {
+    egui::Area::new(egui::Id::new("loading"))
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Loading map...");
+            });
+        });
+
+    Ok(())
+}
+
+fn pause_menu_egui(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<Settings>,
+    mut bindings: ResMut<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut next_state: ResMut<NextState<GameState>>,
+) -> Result {
+    egui::Window::new("Paused")
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .collapsible(false)
+        .resizable(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.label("The simulation is frozen, the camera still moves");
+
+            let resume = format!("Resume ({})", bindings.label(Action::Pause));
+            if ui.button(resume).clicked() {
+                next_state.set(GameState::InGame);
+            }
+
+            ui.collapsing("Settings", |ui| {
+                settings_ui(ui, &mut settings);
+            });
+            ui.collapsing("Controls", |ui| {
+                controls_ui(ui, &mut bindings, &mut rebinding);
+            });
+
+            if ui.button("Quit to Main Menu").clicked() {
+                next_state.set(GameState::MainMenu);
+            }
+        });
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::ecs::query::Allow;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::buildings::helpers::{Building, BuildingKind, TILE_SIZE};
+use crate::camera::CameraController;
+use crate::game::InWorld;
+use crate::tiles::terrain::{Deposit, Terrain, TerrainMap};
+
+pub struct MinimapEguiPlugin;
+
+impl Plugin for MinimapEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            minimap_egui_window.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+// Side length of the minimap in points, whatever the map size
+const MINIMAP_SIZE: f32 = 192.0;
+
+fn terrain_color(terrain: Terrain) -> egui::Color32 {
+    match terrain {
+        Terrain::Grass => egui::Color32::from_rgb(35, 144, 99),
+        Terrain::Dirt => egui::Color32::from_rgb(140, 98, 64),
+        Terrain::Sand => egui::Color32::from_rgb(222, 196, 120),
+    }
+}
+
+fn deposit_color(deposit: Deposit) -> egui::Color32 {
+    match deposit {
+        Deposit::Oil => egui::Color32::from_rgb(30, 24, 36),
+    }
+}
+
+const VIEWPORT_COLOR: egui::Color32 = egui::Color32::WHITE;
+
+fn building_color(kind: BuildingKind) -> egui::Color32 {
+    match kind {
+        BuildingKind::Pipe => egui::Color32::from_rgb(150, 150, 150),
+        BuildingKind::OilExtractor => egui::Color32::from_rgb(40, 40, 40),
+        BuildingKind::SmallOilContainer
+        | BuildingKind::MediumOilContainer
+        | BuildingKind::LargeOilContainer => egui::Color32::from_rgb(120, 90, 60),
+        BuildingKind::OilRefinery => egui::Color32::from_rgb(200, 120, 50),
+        BuildingKind::Pump | BuildingKind::CheckValve | BuildingKind::OverflowValve => {
+            egui::Color32::from_rgb(90, 140, 200)
+        }
+        BuildingKind::Conveyor | BuildingKind::Inserter => egui::Color32::from_rgb(220, 200, 80),
+        BuildingKind::PowerPole | BuildingKind::GasGenerator => {
+            egui::Color32::from_rgb(230, 70, 60)
+        }
+    }
+}
+
+// One pixel per tile, rows run top to bottom while tile y runs upwards
+fn terrain_image(terrain: &TerrainMap) -> egui::ColorImage {
+    let size = terrain.size;
+    let mut image = egui::ColorImage::filled([size as usize; 2], egui::Color32::BLACK);
+
+    for x in 0..size {
+        for y in 0..size {
+            let ground = terrain_color(terrain.ground(x, y));
+            // Deposits fade into the ground as they deplete
+            let color = match terrain.deposit(x, y) {
+                Some(deposit) => {
+                    ground.lerp_to_gamma(deposit_color(deposit.kind), deposit.remaining())
+                }
+                None => ground,
+            };
+            image[(x as usize, (size - 1 - y) as usize)] = color;
+        }
+    }
+
+    image
+}
+
+fn minimap_egui_window(
+    mut contexts: EguiContexts,
+    q_camera: Single<(&mut Transform, &Projection), With<CameraController>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    // Planned and deconstructing buildings are disabled but still on the map
+    q_buildings: Query<
+        (&BuildingKind, &GlobalTransform, Has<Disabled>),
+        (With<Building>, Allow<Disabled>),
+    >,
+    terrain: Res<TerrainMap>,
+    mut terrain_texture: Local<Option<egui::TextureHandle>>,
+) -> Result {
+    let ctx = contexts.ctx_mut()?;
+
+    // Drawn into a texture that is only redrawn when the map or a deposit changes
+    if terrain.is_changed() || terrain_texture.is_none() {
+        let image = terrain_image(&terrain);
+        match terrain_texture.as_mut() {
+            Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+            None => {
+                *terrain_texture =
+                    Some(ctx.load_texture("minimap_terrain", image, egui::TextureOptions::NEAREST))
+            }
+        }
+    }
+    let Some(texture) = terrain_texture.as_ref() else {
+        return Ok(());
+    };
+
+    let (mut camera_transform, projection) = q_camera.into_inner();
+    let zoom = match projection {
+        Projection::Orthographic(projection2d) => projection2d.scale,
+        _ => 1.0,
+    };
+
+    // The tilemap is anchored on its center at the origin
+    let half_map = terrain.size as f32 * TILE_SIZE / 2.0;
+
+    egui::Window::new("Minimap")
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+        .resizable(false)
+        .show(ctx, |ui| {
+            let (rect, response) = ui.allocate_exact_size(
+                egui::vec2(MINIMAP_SIZE, MINIMAP_SIZE),
+                egui::Sense::click_and_drag(),
+            );
+            let painter = ui.painter_at(rect);
+            let scale = MINIMAP_SIZE / (half_map * 2.0);
+
+            let to_minimap = |world: Vec2| {
+                egui::pos2(
+                    rect.left() + (world.x + half_map) * scale,
+                    rect.top() + (half_map - world.y) * scale,
+                )
+            };
+
+            painter.image(
+                texture.id(),
+                rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+
+            for (kind, transform, disabled) in &q_buildings {
+                let center = to_minimap(transform.translation().truncate());
+                let size = (kind.footprint() as f32 * TILE_SIZE * scale).max(2.0);
+                let mut color = building_color(*kind);
+                if disabled {
+                    color = color.gamma_multiply(0.5);
+                }
+                painter.rect_filled(
+                    egui::Rect::from_center_size(center, egui::vec2(size, size)),
+                    0.0,
+                    color,
+                );
+            }
+
+            let half_view = window.size() / 2.0 * zoom;
+            let view = camera_transform.translation.truncate();
+            painter.rect_stroke(
+                egui::Rect::from_two_pos(
+                    to_minimap(view - half_view),
+                    to_minimap(view + half_view),
+                ),
+                0.0,
+                egui::Stroke::new(1.0, VIEWPORT_COLOR),
+                egui::StrokeKind::Middle,
+            );
+
+            // Clicking or dragging centers the camera there, the camera clamps it to the map
+            if response.is_pointer_button_down_on()
+                && let Some(pointer) = response.interact_pointer_pos()
+            {
+                let offset = pointer - rect.left_top();
+                camera_transform.translation.x = offset.x / scale - half_map;
+                camera_transform.translation.y = half_map - offset.y / scale;
+            }
+        });
+
+    Ok(())
+}
@@ -1 +1,12 @@
+pub mod alerts;
+pub mod console;
 pub mod debug;
+pub mod hover;
+pub mod inventory;
+pub mod menu;
+pub mod minimap;
+pub mod networks;
+pub mod power;
+pub mod research;
+pub mod stats;
+pub mod toolbar;
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::fluids::network::{FlushPipeNetworkMsg, PipeNetworks};
+use crate::game::InWorld;
+
+pub struct PipeNetworksEguiPlugin;
+
+impl Plugin for PipeNetworksEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            pipe_networks_egui_window.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+fn pipe_networks_egui_window(
+    mut contexts: EguiContexts,
+    networks: Res<PipeNetworks>,
+    mut flush_writer: MessageWriter<FlushPipeNetworkMsg>,
+) -> Result {
+    egui::Window::new("Pipe Networks")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            if networks.networks.is_empty() {
+                ui.label("No pipes placed");
+            }
+
+            for (index, network) in networks.networks.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("#{} ({} pipes)", index, network.pipes.len()));
+
+                    match network.fluid {
+                        Some(fluid) => {
+                            let [r, g, b] = fluid.ui_color();
+                            ui.colored_label(egui::Color32::from_rgb(r, g, b), fluid.name());
+                        }
+                        None => {
+                            ui.label("Empty");
+                        }
+                    }
+
+                    // Any pipe tile identifies the network for the flush
+                    if ui.button("Flush").clicked()
+                        && let Some(tile) = network.tiles.first()
+                    {
+                        flush_writer.write(FlushPipeNetworkMsg { tile: *tile });
+                    }
+                });
+
+                ui.add(
+                    egui::ProgressBar::new(network.fill_ratio())
+                        .text(format!("{:.0} / {:.0}", network.amount, network.capacity)),
+                );
+
+                if let Some(error) = network.error {
+                    let text = if network.mixed {
+                        format!("Mixed fluids ({}), flush to fix", error)
+                    } else {
+                        format!("Blocked: {}", error)
+                    };
+                    ui.colored_label(egui::Color32::from_rgb(230, 70, 60), text);
+                }
+
+                ui.separator();
+            }
+        });
+
+    Ok(())
+}
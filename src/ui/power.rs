@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::game::InWorld;
+use crate::power::PowerGrids;
+
+pub struct PowerGridsEguiPlugin;
+
+impl Plugin for PowerGridsEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            power_grids_egui_window.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+fn power_grids_egui_window(mut contexts: EguiContexts, grids: Res<PowerGrids>) -> Result {
+    egui::Window::new("Power Grids")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            if grids.grids.is_empty() {
+                ui.label("No power poles placed");
+            }
+
+            for (index, grid) in grids.grids.iter().enumerate() {
+                ui.label(format!(
+                    "#{} ({} poles, {} generators, {} consumers)",
+                    index,
+                    grid.poles.len(),
+                    grid.producers,
+                    grid.consumers
+                ));
+                ui.label(format!(
+                    "Production: {:.0} / {:.0} kW",
+                    grid.production, grid.capacity
+                ));
+                ui.label(format!("Demand: {:.0} kW", grid.demand));
+
+                let color = if grid.satisfaction >= 1.0 {
+                    egui::Color32::from_rgb(145, 219, 105)
+                } else if grid.satisfaction > 0.5 {
+                    egui::Color32::from_rgb(251, 185, 84)
+                } else {
+                    egui::Color32::from_rgb(230, 70, 60)
+                };
+
+                ui.add(
+                    egui::ProgressBar::new(grid.satisfaction)
+                        .fill(color)
+                        .text(format!("{:.0}% satisfied", grid.satisfaction * 100.0)),
+                );
+
+                ui.separator();
+            }
+        });
+
+    Ok(())
+}
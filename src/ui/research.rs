@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::game::InWorld;
+use crate::research::tree::{TECHNOLOGIES, Technology, technology};
+use crate::research::{Research, StartResearchMsg};
+
+pub struct ResearchEguiPlugin;
+
+impl Plugin for ResearchEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            research_egui_window.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+fn cost_label(research: &Research, tech: &Technology, in_progress: bool) -> String {
+    tech.cost
+        .iter()
+        .map(|(material, amount)| {
+            if in_progress {
+                format!(
+                    "{:.0}/{:.0} {}",
+                    research.paid(*material).min(*amount),
+                    amount,
+                    material
+                )
+            } else {
+                format!("{:.0} {}", amount, material)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn unlocks_label(tech: &Technology) -> String {
+    tech.unlocks
+        .iter()
+        .map(|kind| kind.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn research_egui_window(
+    mut contexts: EguiContexts,
+    research: Res<Research>,
+    mut msg_writer: MessageWriter<StartResearchMsg>,
+) -> Result {
+    egui::Window::new("Research")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            match research.current.and_then(technology) {
+                Some(tech) => {
+                    ui.strong(tech.name);
+                    ui.add(
+                        egui::ProgressBar::new(research.progress)
+                            .text(format!("{:.0}%", research.progress * 100.0)),
+                    );
+                    ui.label(cost_label(&research, tech, true));
+                    ui.label("Paid from the inventory, then from central storage");
+                }
+                None => {
+                    ui.label("Nothing being researched");
+                }
+            }
+
+            ui.separator();
+            ui.label("Available");
+
+            for tech in research.available() {
+                ui.horizontal(|ui| {
+                    let running = research.current == Some(tech.id);
+                    if ui
+                        .add_enabled(!running, egui::Button::new(tech.name))
+                        .clicked()
+                    {
+                        msg_writer.write(StartResearchMsg { id: tech.id });
+                    }
+                    ui.label(format!("{:.0}s", tech.duration));
+                });
+                ui.label(format!("Cost: {}", cost_label(&research, tech, false)));
+                ui.label(format!("Unlocks: {}", unlocks_label(tech)));
+                ui.add_space(4.0);
+            }
+
+            ui.separator();
+            ui.collapsing("Completed", |ui| {
+                for tech in TECHNOLOGIES
+                    .iter()
+                    .filter(|tech| research.completed.contains(tech.id))
+                {
+                    ui.label(format!("{} ({})", tech.name, unlocks_label(tech)));
+                }
+            });
+        });
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::game::InWorld;
+use crate::stats::{Material, ProductionStats, SAMPLES_PER_WINDOW, StatWindow};
+
+pub struct ProductionStatsEguiPlugin;
+
+impl Plugin for ProductionStatsEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            production_stats_egui_window.run_if(in_state(InWorld)),
+        );
+    }
+}
+
+const PRODUCED_COLOR: egui::Color32 = egui::Color32::from_rgb(145, 219, 105);
+const CONSUMED_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 70, 60);
+
+fn production_stats_egui_window(
+    mut contexts: EguiContexts,
+    stats: Res<ProductionStats>,
+    mut window: Local<StatWindow>,
+) -> Result {
+    egui::Window::new("Production")
+        .default_open(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                for option in StatWindow::ALL {
+                    ui.selectable_value(&mut *window, option, option.label());
+                }
+            });
+
+            let materials = stats.materials();
+            if materials.is_empty() {
+                ui.label("Nothing produced yet");
+            }
+
+            for material in materials {
+                ui.separator();
+                production_graph(ui, &stats, material, *window);
+                production_table(ui, &stats, material, *window);
+            }
+        });
+
+    Ok(())
+}
+
+fn production_graph(
+    ui: &mut egui::Ui,
+    stats: &ProductionStats,
+    material: Material,
+    window: StatWindow,
+) {
+    let produced = stats.produced_samples(material, window);
+    let consumed = stats.consumed_samples(material, window);
+
+    ui.horizontal(|ui| {
+        ui.strong(material.name());
+        ui.colored_label(
+            PRODUCED_COLOR,
+            format!(
+                "+{:.1}/min",
+                stats.produced_per_minute(material, None, window)
+            ),
+        );
+        ui.colored_label(
+            CONSUMED_COLOR,
+            format!(
+                "-{:.1}/min",
+                stats.consumed_per_minute(material, None, window)
+            ),
+        );
+    });
+
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(240.0, 60.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    painter.rect_filled(rect, 2.0, egui::Color32::from_rgb(46, 34, 47));
+
+    // Both lines share a scale so they can be compared
+    let max = produced
+        .iter()
+        .chain(consumed.iter())
+        .fold(0.0f32, |max, value| max.max(*value));
+
+    if max > 0.0 {
+        for (samples, color) in [(produced, PRODUCED_COLOR), (consumed, CONSUMED_COLOR)] {
+            let points: Vec<egui::Pos2> = samples
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let x = rect.left() + rect.width() * i as f32 / (SAMPLES_PER_WINDOW - 1) as f32;
+                    let y = rect.bottom() - rect.height() * (value / max) * 0.9;
+                    egui::pos2(x, y)
+                })
+                .collect();
+
+            painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+        }
+    }
+
+    painter.rect_stroke(
+        rect,
+        2.0,
+        egui::Stroke::new(1.0, egui::Color32::from_rgb(98, 85, 101)),
+        egui::StrokeKind::Inside,
+    );
+}
+
+fn production_table(
+    ui: &mut egui::Ui,
+    stats: &ProductionStats,
+    material: Material,
+    window: StatWindow,
+) {
+    egui::Grid::new(("production_table", material.name()))
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Building");
+            ui.label("Produced/min");
+            ui.label("Consumed/min");
+            ui.end_row();
+
+            for building in stats.buildings(material) {
+                ui.label(building.name());
+                ui.label(format!(
+                    "{:.1}",
+                    stats.produced_per_minute(material, Some(building), window)
+                ));
+                ui.label(format!(
+                    "{:.1}",
+                    stats.consumed_per_minute(material, Some(building), window)
+                ));
+                ui.end_row();
+            }
+        });
+}
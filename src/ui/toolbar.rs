@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, EguiTextureHandle, egui};
+
+use crate::bindings::{Action, Actions};
+use crate::buildings::helpers::{
+    CancelPlacementMsg, DeleteMode, delete_selected_buildings, highlight_buildings_to_delete,
+    select_buildings_to_delete,
+};
+use crate::buildings::placement::{PlacementSystems, StartPlacementMsg};
+use crate::buildings::registry::{BuildingCategory, BuildingInfo, BuildingRegistry};
+use crate::game::InWorld;
+use crate::overlay::UtilizationOverlay;
+use crate::pollution::PollutionOverlay;
+use crate::research::Research;
+
+pub struct BuildToolbarEguiPlugin;
+
+impl Plugin for BuildToolbarEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DeleteMode>()
+            .add_systems(
+                Update,
+                (
+                    select_buildings_to_delete,
+                    delete_selected_buildings,
+                    highlight_buildings_to_delete,
+                )
+                    .chain()
+                    .after(PlacementSystems::Start),
+            )
+            .add_systems(OnExit(InWorld), reset_delete_mode)
+            .add_systems(
+                EguiPrimaryContextPass,
+                build_toolbar_egui.run_if(in_state(InWorld)),
+            );
+    }
+}
+
+const ICON_FPS: f32 = 10.0;
+
+// Leaving the world doesn't carry delete mode into the next game
+fn reset_delete_mode(mut delete_mode: ResMut<DeleteMode>) {
+    *delete_mode = DeleteMode::default();
+}
+
+// Quick slots for the first unlocked buildings, in toolbar order
+const SLOTS: usize = 10;
+
+/// Icon of a building as an egui image, animated icons cycle with `elapsed`.
+pub fn building_icon(
+    contexts: &mut EguiContexts,
+    asset_server: &AssetServer,
+    info: &BuildingInfo,
+    elapsed: f32,
+) -> egui::Image<'static> {
+    let icon = info.icon;
+    let texture = contexts.add_image(EguiTextureHandle::Strong(asset_server.load(icon.texture)));
+
+    let frame = icon.index + (elapsed * ICON_FPS) as u32 % icon.frames;
+    let u_min = frame as f32 / icon.columns as f32;
+    let u_max = (frame + 1) as f32 / icon.columns as f32;
+
+    let size = 32.0 * info.kind.footprint() as f32;
+
+    egui::Image::new(egui::load::SizedTexture::new(
+        texture,
+        egui::vec2(size, size),
+    ))
+    .uv(egui::Rect::from_min_max(
+        egui::pos2(u_min, 0.0),
+        egui::pos2(u_max, 1.0),
+    ))
+}
+
+fn tooltip(info: &BuildingInfo, shortcut: Option<String>) -> String {
+    let kind = info.kind;
+    let cost = kind
+        .cost()
+        .iter()
+        .map(|(material, amount)| format!("{:.0} {}", amount, material))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut text = format!(
+        "{}\nCost: {}\nFootprint: {}x{} tiles\nBuild time: {:.1}s",
+        kind.name(),
+        cost,
+        kind.footprint(),
+        kind.footprint(),
+        kind.build_time()
+    );
+    if let Some(key) = shortcut {
+        text.push_str(&format!("\nShortcut: {}", key));
+    }
+    text
+}
+
+fn build_toolbar_egui(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    asset_server: Res<AssetServer>,
+    registry: Res<BuildingRegistry>,
+    research: Res<Research>,
+    time: Res<Time<Real>>,
+    actions: Actions,
+    mut delete_mode: ResMut<DeleteMode>,
+    mut overlay: ResMut<UtilizationOverlay>,
+    mut pollution_overlay: ResMut<PollutionOverlay>,
+) -> Result {
+    // Grouped the way they are shown, so shortcuts follow what's on screen
+    let groups: Vec<(BuildingCategory, Vec<&BuildingInfo>)> = BuildingCategory::ALL
+        .iter()
+        .map(|category| {
+            let buildings = registry
+                .buildings
+                .iter()
+                .filter(|info| info.category == *category && research.is_unlocked(info.kind))
+                .collect();
+            (*category, buildings)
+        })
+        .collect();
+
+    let mut picked = None;
+    let mut slot = 0;
+
+    // Icons, collected first since they need the egui contexts mutably
+    let mut icons = Vec::new();
+    for (_, buildings) in groups.iter() {
+        for info in buildings.iter() {
+            icons.push(building_icon(
+                &mut contexts,
+                &asset_server,
+                info,
+                time.elapsed_secs(),
+            ));
+        }
+    }
+    let mut icons = icons.into_iter();
+
+    let ctx = contexts.ctx_mut()?;
+
+    egui::TopBottomPanel::bottom("build_toolbar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            for (category, buildings) in groups.iter() {
+                if buildings.is_empty() {
+                    continue;
+                }
+
+                ui.vertical(|ui| {
+                    ui.small(category.name());
+                    ui.horizontal(|ui| {
+                        for info in buildings.iter() {
+                            let shortcut = (slot < SLOTS).then_some(Action::ToolbarSlot(slot));
+                            slot += 1;
+
+                            let Some(icon) = icons.next() else {
+                                continue;
+                            };
+
+                            let response = ui.add(egui::ImageButton::new(icon)).on_hover_text(
+                                tooltip(info, shortcut.map(|action| actions.label(action))),
+                            );
+
+                            if response.clicked()
+                                || shortcut.is_some_and(|action| actions.just_pressed(action))
+                            {
+                                picked = Some(info.kind);
+                            }
+                        }
+                    });
+                });
+
+                ui.separator();
+            }
+
+            ui.vertical(|ui| {
+                ui.small("Tools");
+                ui.toggle_value(&mut delete_mode.active, "Delete");
+                let overlay_label = format!("Overlay ({})", actions.label(Action::ToggleOverlay));
+                ui.toggle_value(&mut overlay.active, overlay_label);
+                let pollution_label =
+                    format!("Pollution ({})", actions.label(Action::TogglePollution));
+                ui.toggle_value(&mut pollution_overlay.active, pollution_label);
+            });
+        });
+    });
+
+    if delete_mode.confirming {
+        egui::Window::new("Confirm deletion")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Deconstruct {} buildings?",
+                    delete_mode.selection.len()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Delete").clicked() {
+                        delete_mode.confirming = false;
+                        delete_mode.confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        delete_mode.clear_selection();
+                    }
+                });
+            });
+    }
+
+    if let Some(kind) = picked {
+        // Only one preview follows the cursor at a time
+        commands.queue(move |world: &mut World| {
+            world.write_message(CancelPlacementMsg);
+            world.write_message(StartPlacementMsg(kind));
+        });
+    }
+
+    Ok(())
+}